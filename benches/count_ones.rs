@@ -0,0 +1,16 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nbitmask::BitMask;
+
+fn count_ones(c: &mut Criterion) {
+    let mut mask: BitMask<u64> = BitMask::zeros(1_000_000);
+    for index in (0..1_000_000).step_by(3) {
+        mask.set(index, true).unwrap();
+    }
+
+    c.bench_function("count_ones 1M bits u64", |b| {
+        b.iter(|| black_box(&mask).count_ones())
+    });
+}
+
+criterion_group!(benches, count_ones);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+use num_bigint::BigUint;
+
+use crate::bit_storage::BitStorage;
+use crate::error::BitMaskError;
+use crate::BitMask;
+
+impl<T: BitStorage> BitMask<T> {
+    ///Converts the mask into a num-bigint BigUint, bit *i* of the mask being the bit of weight 2^i
+    pub fn to_biguint(&self) -> BigUint {
+        let mut bytes = Vec::with_capacity(self.mask.len() * T::SIZE / 8);
+        for m in &self.mask {
+            bytes.extend(m.to_be_bytes().into_iter().rev());
+        }
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    ///Creates a new BitMask of *length* holding the bits of the given num-bigint BigUint, the bit of weight 2^i being bit *i* of the mask.
+    ///Returns BitMaskError::Overflow if *value* doesn't fit within *length* bits
+    pub fn from_biguint(value: &BigUint, length: usize) -> Result<BitMask<T>, BitMaskError> {
+        if value.bits() > length as u64 {
+            return Err(BitMaskError::Overflow);
+        }
+
+        let mut bytes = value.to_bytes_le();
+        bytes.resize(length.div_ceil(T::SIZE) * T::SIZE / 8, 0);
+
+        let mask: Result<Vec<T>, BitMaskError> = bytes
+            .chunks(T::SIZE / 8)
+            .map(|chunk| T::from_be_bytes(&chunk.iter().rev().copied().collect::<Vec<u8>>()))
+            .collect();
+
+        Ok(BitMask {
+            mask: mask?,
+            length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_biguint_round_trip() {
+        let mut mask: BitMask<u16> = BitMask::zeros(70);
+        mask.set(0, true).unwrap();
+        mask.set(3, true).unwrap();
+        mask.set(69, true).unwrap();
+
+        let value = mask.to_biguint();
+        assert_eq!(
+            value,
+            (BigUint::from(1u8) << 69u32) + BigUint::from(0b1001u8)
+        );
+
+        let mask2: BitMask<u16> = BitMask::from_biguint(&value, 70).unwrap();
+        assert_eq!(mask2, mask);
+
+        let mask3: BitMask<u64> = BitMask::from_biguint(&value, 70).unwrap();
+        assert_eq!(mask3.to_string(), mask.to_string());
+
+        assert_eq!(
+            BitMask::<u16>::from_biguint(&value, 69)
+                .unwrap_err()
+                .to_string(),
+            "Overflow"
+        );
+        assert_eq!(BitMask::<u8>::zeros(10).to_biguint(), BigUint::from(0u8));
+    }
+}
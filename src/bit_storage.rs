@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::error::BitMaskError;
 
 ///The trait required for any Container
@@ -15,6 +17,9 @@ pub trait BitStorage {
     ///Returns the number of trailing zeros in the binary representation of self.
     fn trailing_zeros(&self) -> usize;
 
+    ///Returns the number of leading zeros in the binary representation of self.
+    fn leading_zeros(&self) -> usize;
+
     ///Return the memory representation of this BitStorage as a byte array in big-endian (network) byte order.
     fn to_be_bytes(&self) -> Vec<u8>;
 
@@ -25,6 +30,17 @@ pub trait BitStorage {
     fn from_be_bytes(value: &[u8]) -> Result<Self, BitMaskError>
     where
         Self: Sized;
+
+    ///Return the memory representation of this BitStorage as a byte array in little-endian byte order.
+    fn to_le_bytes(&self) -> Vec<u8>;
+
+    /// Create a BitStorage value from its representation as a byte array in little endian.
+    /// Returns a Result that contains either :
+    /// - a valid BitStorage
+    /// - a BitMaskError if the transformation failed (for example if the number of bytes given in parameter is not equal to SIZE/8)
+    fn from_le_bytes(value: &[u8]) -> Result<Self, BitMaskError>
+    where
+        Self: Sized;
 }
 
 macro_rules! bit_storage_impl_primitive {
@@ -42,6 +58,10 @@ macro_rules! bit_storage_impl_primitive {
                 $t::trailing_zeros(*self) as usize
             }
 
+            fn leading_zeros(&self) -> usize {
+                $t::leading_zeros(*self) as usize
+            }
+
             fn to_be_bytes(&self) -> Vec<u8> {
                 $t::to_be_bytes(*self).to_vec()
             }
@@ -53,6 +73,18 @@ macro_rules! bit_storage_impl_primitive {
                         .map_err(|_| BitMaskError::DeserializationFailed)?,
                 ))
             }
+
+            fn to_le_bytes(&self) -> Vec<u8> {
+                $t::to_le_bytes(*self).to_vec()
+            }
+
+            fn from_le_bytes(value: &[u8]) -> Result<Self, BitMaskError> {
+                Ok($t::from_le_bytes(
+                    value
+                        .try_into()
+                        .map_err(|_| BitMaskError::DeserializationFailed)?,
+                ))
+            }
         }
     };
 }
@@ -62,3 +94,4 @@ bit_storage_impl_primitive!(u16);
 bit_storage_impl_primitive!(u32);
 bit_storage_impl_primitive!(u64);
 bit_storage_impl_primitive!(u128);
+bit_storage_impl_primitive!(usize);
@@ -0,0 +1,55 @@
+use std::ops::{BitAnd, BitAndAssign, BitOrAssign, Not, Shl, Shr};
+
+use bitvec::vec::BitVec;
+
+use crate::bit_storage::BitStorage;
+use crate::BitMask;
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+{
+    ///Converts the mask into a bitvec BitVec holding the same logical bits, index 0 first
+    pub fn to_bitvec(&self) -> BitVec {
+        (0..self.length)
+            .map(|index| self.get(index).unwrap_or(false))
+            .collect()
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + Clone + Not<Output = T> + BitAndAssign + BitOrAssign + Shl<usize, Output = T>,
+{
+    ///Creates a new BitMask holding the same logical bits as the given bitvec BitVec
+    pub fn from_bitvec(bits: &BitVec) -> BitMask<T> {
+        let mut mask = BitMask::zeros(bits.len());
+        for index in bits.iter_ones() {
+            let _ = mask.set(index, true);
+        }
+        mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitvec_round_trip() {
+        let mut mask: BitMask<u8> = BitMask::zeros(19);
+        mask.set(0, true).unwrap();
+        mask.set(3, true).unwrap();
+        mask.set(18, true).unwrap();
+
+        let bits = mask.to_bitvec();
+        assert_eq!(bits.len(), 19);
+        assert_eq!(bits.iter_ones().collect::<Vec<_>>(), vec![0, 3, 18]);
+
+        let mask2: BitMask<u8> = BitMask::from_bitvec(&bits);
+        assert_eq!(mask2, mask);
+
+        let mask3: BitMask<u64> = BitMask::from_bitvec(&bits);
+        assert_eq!(mask3.to_string(), mask.to_string());
+    }
+}
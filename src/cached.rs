@@ -0,0 +1,157 @@
+use core::fmt::{Debug, Formatter};
+use core::ops::{
+    BitAnd, BitAndAssign, BitOrAssign, BitXorAssign, Deref, Not, Shl, ShlAssign, Shr, ShrAssign,
+    Sub,
+};
+
+use crate::bit_storage::BitStorage;
+use crate::error::BitMaskError;
+use crate::BitMask;
+
+///Wrapper around a BitMask keeping its number of bits set to BitStorage::ONE up to date, count_ones() being O(1).
+///Read-only methods of the wrapped BitMask are available through Deref, every mutation goes through the wrapper.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CachedBitMask<T> {
+    mask: BitMask<T>,
+    ones: usize,
+}
+
+impl<T> Debug for CachedBitMask<T>
+where
+    BitMask<T>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CachedBitMask")
+            .field("mask", &self.mask)
+            .field("ones", &self.ones)
+            .finish()
+    }
+}
+
+impl<T> CachedBitMask<T>
+where
+    T: BitStorage + Clone,
+{
+    ///Wraps *mask*, computing its number of bits set once
+    pub fn new(mask: BitMask<T>) -> CachedBitMask<T> {
+        let ones = mask.count_ones();
+        CachedBitMask { mask, ones }
+    }
+
+    ///Returns the cached number of bits set to BitStorage::ONE within the mask
+    pub fn count_ones(&self) -> usize {
+        self.ones
+    }
+
+    ///Returns the wrapped BitMask
+    pub fn into_inner(self) -> BitMask<T> {
+        self.mask
+    }
+
+    ///Applies *f* to the wrapped BitMask then recomputes the cached count
+    fn update(&mut self, f: impl FnOnce(&mut BitMask<T>)) {
+        f(&mut self.mask);
+        self.ones = self.mask.count_ones();
+    }
+}
+
+impl<T> CachedBitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + PartialEq
+        + Not<Output = T>
+        + BitAnd<Output = T>
+        + BitAndAssign
+        + BitOrAssign
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>
+        + Sub<Output = T>,
+{
+    ///Sets bit at *index* to true or false, adjusting the cached count
+    pub fn set(&mut self, index: usize, value: bool) -> Result<(), BitMaskError> {
+        let previous = self.mask.get(index)?;
+        self.mask.set(index, value)?;
+        match (previous, value) {
+            (false, true) => self.ones += 1,
+            (true, false) => self.ones -= 1,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    ///Sets all the bits to *value*
+    pub fn set_all(&mut self, value: bool) {
+        self.mask.set_all(value);
+        self.ones = if value { self.mask.length() } else { 0 };
+    }
+
+    ///Sets all the bits to BitStorage::ZERO
+    pub fn clear(&mut self) {
+        self.set_all(false);
+    }
+}
+
+impl<T> Deref for CachedBitMask<T> {
+    type Target = BitMask<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.mask
+    }
+}
+
+impl<T> From<BitMask<T>> for CachedBitMask<T>
+where
+    T: BitStorage + Clone,
+{
+    fn from(mask: BitMask<T>) -> Self {
+        CachedBitMask::new(mask)
+    }
+}
+
+impl<T> BitOrAssign<&BitMask<T>> for CachedBitMask<T>
+where
+    T: BitStorage + Clone + BitOrAssign,
+{
+    fn bitor_assign(&mut self, rhs: &BitMask<T>) {
+        self.update(|mask| *mask |= rhs);
+    }
+}
+
+impl<T> BitAndAssign<&BitMask<T>> for CachedBitMask<T>
+where
+    T: BitStorage + Clone + BitAndAssign,
+{
+    fn bitand_assign(&mut self, rhs: &BitMask<T>) {
+        self.update(|mask| *mask &= rhs);
+    }
+}
+
+impl<T> BitXorAssign<&BitMask<T>> for CachedBitMask<T>
+where
+    T: BitStorage + Clone + BitXorAssign,
+{
+    fn bitxor_assign(&mut self, rhs: &BitMask<T>) {
+        self.update(|mask| *mask ^= rhs);
+    }
+}
+
+impl<T> ShlAssign<usize> for CachedBitMask<T>
+where
+    T: BitStorage + Clone,
+    BitMask<T>: ShlAssign<usize>,
+{
+    fn shl_assign(&mut self, rhs: usize) {
+        self.update(|mask| *mask <<= rhs);
+    }
+}
+
+impl<T> ShrAssign<usize> for CachedBitMask<T>
+where
+    T: BitStorage + Clone,
+    BitMask<T>: ShrAssign<usize>,
+{
+    fn shr_assign(&mut self, rhs: usize) {
+        self.update(|mask| *mask >>= rhs);
+    }
+}
@@ -1,9 +1,11 @@
-use std::fmt;
+use core::fmt;
 
 #[derive(Clone, Debug)]
 pub enum BitMaskError {
     IndexOutOfBounds,
     DeserializationFailed,
+    Overflow,
+    LengthMismatch,
 }
 
 impl fmt::Display for BitMaskError {
@@ -11,6 +13,11 @@ impl fmt::Display for BitMaskError {
         match self {
             BitMaskError::IndexOutOfBounds => write!(f, "IndexOutOfBounds"),
             BitMaskError::DeserializationFailed => write!(f, "DeserializationFailed"),
+            BitMaskError::Overflow => write!(f, "Overflow"),
+            BitMaskError::LengthMismatch => write!(f, "LengthMismatch"),
         }
     }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for BitMaskError {}
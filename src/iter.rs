@@ -0,0 +1,84 @@
+use core::fmt::{Debug, Formatter};
+use core::iter::FusedIterator;
+use core::ops::{BitAnd, Shr};
+
+use crate::bit_storage::BitStorage;
+use crate::BitMask;
+
+///Iterator over the logical bits of a BitMask, returned by BitMask::bits().
+///It can be reversed to read the bits from index length - 1 down to index 0.
+#[derive(Clone)]
+pub struct Bits<'a, T> {
+    mask: &'a BitMask<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Bits<'a, T> {
+    pub(crate) fn new(mask: &'a BitMask<T>) -> Bits<'a, T> {
+        Bits {
+            mask,
+            front: 0,
+            back: mask.length,
+        }
+    }
+}
+
+impl<T> Debug for Bits<'_, T>
+where
+    BitMask<T>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Bits")
+            .field("mask", self.mask)
+            .field("front", &self.front)
+            .field("back", &self.back)
+            .finish()
+    }
+}
+
+impl<T> Iterator for Bits<'_, T>
+where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+{
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let value = self.mask.get(self.front).ok()?;
+        self.front += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Bits<'_, T>
+where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        //back is at most length, so the padding bits are never read
+        let value = self.mask.get(self.back - 1).ok()?;
+        self.back -= 1;
+        Some(value)
+    }
+}
+
+impl<T> ExactSizeIterator for Bits<'_, T> where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>
+{
+}
+
+impl<T> FusedIterator for Bits<'_, T> where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>
+{
+}
@@ -1,137 +1,1472 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(clippy::all, clippy::unwrap_used)]
 
+extern crate alloc;
+
 pub mod bit_storage;
+pub mod cached;
 pub mod error;
+pub mod iter;
+pub mod rank;
 
 #[cfg(feature = "serde")]
 pub mod serde;
-use std::fmt::Binary;
 
-use std::ops::{BitXor, BitXorAssign, Not, Shl, ShlAssign, Shr, ShrAssign, Sub};
+#[cfg(feature = "bitvec")]
+pub mod bitvec;
+
+#[cfg(feature = "num-bigint")]
+pub mod biguint;
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use core::ops::{
+    BitXor, BitXorAssign, Bound, Index, Not, RangeBounds, Shl, ShlAssign, Shr, ShrAssign, Sub,
+};
 
-use std::{
-    fmt::{Display, Formatter},
+use core::{
+    fmt::{Binary, Debug, Display, Formatter},
     ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign},
+    str::FromStr,
 };
 
 use bit_storage::BitStorage;
 use error::BitMaskError;
+use iter::Bits;
+use rank::RankIndex;
 
 ///Struct storing the bitmask in a vec of BitStorage T.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct BitMask<T> {
     mask: Vec<T>,
     length: usize,
 }
 impl<T> BitMask<T>
 where
-    T: BitStorage + Clone,
+    T: BitStorage + Clone,
+{
+    ///Creates a new BitMask of *size* and fill it with BitStorage::ZERO
+    pub fn zeros(size: usize) -> BitMask<T> {
+        BitMask {
+            mask: vec![T::ZERO; size.div_ceil(T::SIZE)],
+            length: size,
+        }
+    }
+
+    ///Returns BitMask's length
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    ///Grows the mask to *len* bits, the new bits being set to BitStorage::ZERO. Does nothing if *len* isn't greater than *length*
+    pub fn grow_to(&mut self, len: usize) {
+        if len > self.length {
+            self.length = len;
+            self.mask.resize(len.div_ceil(T::SIZE), T::ZERO);
+        }
+    }
+
+    ///Resizes the mask to exactly *len* bits, shared by the assign operators : |= and ^= reconcile to the max of both lengths, &= to the min.
+    ///New bits are set to BitStorage::ZERO. When shrinking, the bits beyond *len* in the top block are left untouched, the caller must clear them
+    ///unless they are known to be BitStorage::ZERO (which is the case for &=, as the shorter operand has no bit set there)
+    fn reconcile_len(&mut self, len: usize) {
+        self.length = len;
+        self.mask.resize(len.div_ceil(T::SIZE), T::ZERO);
+    }
+
+    ///Returns the number of BitStorage units backing the mask
+    pub fn block_count(&self) -> usize {
+        self.mask.len()
+    }
+
+    ///Drops the BitStorage units beyond the ceil(length / T::SIZE) needed to hold the mask, and releases the memory they used
+    pub fn shrink_to_fit(&mut self) {
+        self.mask.truncate(self.length.div_ceil(T::SIZE));
+        self.mask.shrink_to_fit();
+    }
+
+    ///Returns the number of bits held by the BitStorage units backing the mask, block_count() * T::SIZE.
+    ///The bits within [length:padded_len-1] are always set to BitStorage::ZERO
+    pub fn padded_len(&self) -> usize {
+        self.mask.len() * T::SIZE
+    }
+
+    ///Returns the number of bytes used by the BitStorage units backing the mask
+    pub fn byte_len(&self) -> usize {
+        self.mask.len() * T::SIZE / 8
+    }
+
+    ///Returns the number of bits set to BitStorage::ONE within the mask
+    pub fn count_ones(&self) -> usize {
+        //Blocks are processed in groups of 4 with independent accumulators, so the compiler can autovectorize the popcounts
+        let chunks = self.mask.chunks_exact(4);
+        let tail: usize = chunks.remainder().iter().map(|m| m.count_ones()).sum();
+        let mut acc = [0usize; 4];
+        for chunk in chunks {
+            acc[0] += chunk[0].count_ones();
+            acc[1] += chunk[1].count_ones();
+            acc[2] += chunk[2].count_ones();
+            acc[3] += chunk[3].count_ones();
+        }
+        acc.iter().sum::<usize>() + tail
+    }
+
+    ///Returns true iff exactly one bit is set to BitStorage::ONE, stopping at the first block that brings the count above one
+    pub fn has_single_bit(&self) -> bool {
+        let mut found = false;
+        for m in &self.mask {
+            match m.count_ones() {
+                0 => {}
+                1 if !found => found = true,
+                _ => return false,
+            }
+        }
+        found
+    }
+
+    ///Returns the number of trailing BitStorage::ZERO within the mask, starting from index 0.
+    ///The result never exceeds *length* : an all-zero mask returns exactly *length*
+    pub fn trailing_zeros(&self) -> usize {
+        let mut acc = 0;
+        for m in &self.mask {
+            let t = m.trailing_zeros();
+            if t != T::SIZE {
+                return (acc + t).min(self.length);
+            }
+            acc += T::SIZE;
+        }
+        self.length
+    }
+
+    ///Returns the lowest index holding a bit set to BitStorage::ONE, None if no bit is set
+    pub fn first_set(&self) -> Option<usize> {
+        let first = self.trailing_zeros();
+        (first < self.length).then_some(first)
+    }
+}
+
+impl<T: BitStorage> BitMask<T> {
+    ///Returns the BitStorage units backing the mask, bit *i* being bit i % T::SIZE of block i / T::SIZE.
+    ///The bits within [length:padded_len-1] are always set to BitStorage::ZERO
+    pub fn as_block_slice(&self) -> &[T] {
+        &self.mask
+    }
+
+    ///Appends the big-endian bytes of every BitStorage unit of the mask to *buf*, one unit after the other
+    pub fn write_be_bytes_into(&self, buf: &mut Vec<u8>) {
+        buf.reserve(self.mask.len() * T::SIZE / 8);
+        for m in &self.mask {
+            buf.append(&mut m.to_be_bytes());
+        }
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage
+        + Not<Output = T>
+        + Clone
+        + BitAndAssign
+        + Shl<usize, Output = T>
+        + Sub<Output = T>,
+{
+    ///Creates a new BitMask of *size* and fill it with BitStorage::ONE
+    pub fn ones(size: usize) -> BitMask<T> {
+        let mut mask = Self::zeros(size);
+        mask.set_all(true);
+        mask
+    }
+
+    ///Sets all the bits to BitStorage::ONE
+    pub fn set_all(&mut self, value: bool) {
+        let s = if value { !T::ZERO } else { T::ZERO };
+        //Only the BitStorage units holding logical bits are filled, none for a zero-length mask
+        for m in self.mask.iter_mut().take(self.length.div_ceil(T::SIZE)) {
+            *m = s.clone();
+        }
+
+        self.normalize();
+    }
+
+    ///Flips every bit of the mask in place
+    pub fn invert(&mut self) {
+        for m in &mut self.mask {
+            *m = !m.clone();
+        }
+
+        //Removing the bits set to 1 by the not operator beyond length
+        self.normalize();
+    }
+
+    ///Returns the number of consecutive bits equal to *value* starting from index 0, like trailing_zeros() for a false *value*.
+    ///Computed block by block, the blocks being negated to count a run of BitStorage::ONE
+    pub fn trailing_run(&self, value: bool) -> usize {
+        let mut acc = 0;
+        for m in &self.mask {
+            let block = if value { !m.clone() } else { m.clone() };
+            let t = block.trailing_zeros();
+            acc += t;
+            if t != T::SIZE {
+                break;
+            }
+        }
+        acc.min(self.length)
+    }
+
+    ///Returns the number of consecutive bits equal to *value* starting from index length - 1 downwards.
+    ///Only the bits within *length* are considered, the padding of the last block being shifted out before counting
+    pub fn leading_run(&self, value: bool) -> usize {
+        let mut acc = 0;
+        let mut valid = match self.length % T::SIZE {
+            0 => T::SIZE,
+            rem => rem,
+        };
+        for m in self.mask[..self.length.div_ceil(T::SIZE)].iter().rev() {
+            let aligned = m.clone() << (T::SIZE - valid);
+            let block = if value { !aligned } else { aligned };
+            let run = block.leading_zeros().min(valid);
+            acc += run;
+            if run != valid {
+                break;
+            }
+            valid = T::SIZE;
+        }
+        acc
+    }
+
+    ///Returns the complement of self within a mask of length *universe* : bit *i* is set iff it isn't set in self, bits beyond *length* being considered as 0.
+    ///Returns BitMaskError::Overflow if *universe* is lower than *length*, self not fitting within it
+    pub fn complement_in_universe(&self, universe: usize) -> Result<BitMask<T>, BitMaskError> {
+        if universe < self.length {
+            return Err(BitMaskError::Overflow);
+        }
+        let mut res = self.clone();
+        res.mask.resize(universe.div_ceil(T::SIZE), T::ZERO);
+        res.length = universe;
+        Ok(!&res)
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + Not<Output = T> + BitAndAssign + BitOrAssign + Shl<usize, Output = T>,
+{
+    ///Sets bit at *index* to true or false
+    pub fn set(&mut self, index: usize, value: bool) -> Result<(), BitMaskError> {
+        if index >= self.length {
+            return Err(BitMaskError::IndexOutOfBounds);
+        }
+        let i = index / T::SIZE;
+        let offset = index % T::SIZE;
+
+        if let Some(m) = self.mask.get_mut(i) {
+            if value {
+                *m |= T::ONE << offset;
+            } else {
+                *m &= !(T::ONE << offset);
+            }
+            Ok(())
+        } else {
+            Err(BitMaskError::IndexOutOfBounds)
+        }
+    }
+
+    ///Sets every bit whose index is given by *indices* to *value*.
+    ///All the indices are checked first : if one is out of bounds, BitMaskError::IndexOutOfBounds is returned and the mask is left unmodified
+    pub fn set_indices(&mut self, indices: &[usize], value: bool) -> Result<(), BitMaskError> {
+        if indices.iter().any(|&index| index >= self.length) {
+            return Err(BitMaskError::IndexOutOfBounds);
+        }
+        for &index in indices {
+            self.set(index, value)?;
+        }
+        Ok(())
+    }
+
+    ///Sets bit at *index* to true or false without any bounds checking.
+    ///
+    ///# Safety
+    ///
+    ///The caller must guarantee that *index* is within [0:length-1], which ensures the BitStorage unit holding it exists.
+    pub unsafe fn set_unchecked(&mut self, index: usize, value: bool) {
+        debug_assert!(index < self.length);
+        let m = self.mask.get_unchecked_mut(index / T::SIZE);
+        if value {
+            *m |= T::ONE << (index % T::SIZE);
+        } else {
+            *m &= !(T::ONE << (index % T::SIZE));
+        }
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+{
+    /// Returns a Result that can be :
+    /// - the boolean value of the bit at given index, if the index is within [0:length-1]
+    /// - BitMaskError::IndexOutOfBounds if the index is out of bounds
+    pub fn get(&self, index: usize) -> Result<bool, BitMaskError> {
+        if index >= self.length {
+            return Err(BitMaskError::IndexOutOfBounds);
+        }
+        let i = index / T::SIZE;
+        let offset = index % T::SIZE;
+        self.mask
+            .get(i)
+            .map(|m| (m.clone() >> offset) & T::ONE == T::ONE)
+            .ok_or(BitMaskError::IndexOutOfBounds)
+    }
+
+    ///Returns the boolean value of the bit at given index, None if the index is out of bounds
+    pub fn checked_get(&self, index: usize) -> Option<bool> {
+        self.get(index).ok()
+    }
+
+    ///Returns the boolean value of the bit at given index without any bounds checking.
+    ///
+    ///# Safety
+    ///
+    ///The caller must guarantee that *index* is within [0:length-1], which ensures the BitStorage unit holding it exists.
+    pub unsafe fn get_unchecked(&self, index: usize) -> bool {
+        debug_assert!(index < self.length);
+        let m = self.mask.get_unchecked(index / T::SIZE);
+        (m.clone() >> (index % T::SIZE)) & T::ONE == T::ONE
+    }
+
+    ///Returns true if every bit whose index is given by *indices* is set to BitStorage::ONE, out of bounds indices being considered as not set
+    pub fn contains_all(&self, indices: &[usize]) -> bool {
+        indices
+            .iter()
+            .all(|&index| self.get(index).unwrap_or(false))
+    }
+
+    ///Returns true if at least one bit whose index is given by *indices* is set to BitStorage::ONE, out of bounds indices being considered as not set
+    pub fn contains_any(&self, indices: &[usize]) -> bool {
+        indices
+            .iter()
+            .any(|&index| self.get(index).unwrap_or(false))
+    }
+
+    ///Returns the bits of the mask as a string, the highest index first as in a binary literal.
+    ///This is the reverse of Display, which writes index 0 first
+    pub fn to_string_msb_first(&self) -> String {
+        (0..self.length)
+            .rev()
+            .map(|index| {
+                if self.get(index).unwrap_or(false) {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect()
+    }
+
+    ///Returns true if self and *other* have the same length and the same logical bits, whatever their BitStorage types
+    pub fn equals_bits<U>(&self, other: &BitMask<U>) -> bool
+    where
+        U: BitStorage + BitAnd<Output = U> + Clone + PartialEq + Shr<usize, Output = U>,
+    {
+        self.length == other.length
+            && (0..self.length).all(|index| self.get(index).ok() == other.get(index).ok())
+    }
+
+    ///Returns the bits of the mask packed into a u64, index 0 being its lowest bit.
+    ///Returns BitMaskError::Overflow if *length* is greater than 64
+    pub fn try_into_u64(&self) -> Result<u64, BitMaskError> {
+        if self.length > u64::BITS as usize {
+            return Err(BitMaskError::Overflow);
+        }
+        Ok((0..self.length)
+            .filter(|&index| self.get(index).unwrap_or(false))
+            .fold(0, |value, index| value | (1 << index)))
+    }
+
+    ///Returns an iterator over the *length* bits of the mask, index 0 first. It can be reversed to start from index length - 1
+    pub fn bits(&self) -> Bits<'_, T> {
+        Bits::new(self)
+    }
+
+    ///Returns the *length* bits of the mask as booleans, index 0 first
+    pub fn to_bool_vec(&self) -> Vec<bool> {
+        let mut bits = Vec::with_capacity(self.length);
+        for (block_index, m) in self.mask.iter().enumerate() {
+            let size = (self.length - block_index * T::SIZE).min(T::SIZE);
+            bits.extend((0..size).map(|offset| (m.clone() >> offset) & T::ONE == T::ONE));
+        }
+        bits
+    }
+
+    ///Returns the highest index holding a bit set to BitStorage::ONE, None if no bit is set
+    pub fn last_set(&self) -> Option<usize> {
+        let (block_index, block) = self
+            .mask
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, block)| **block != T::ZERO)?;
+        let index = block_index * T::SIZE + T::SIZE - 1 - block.leading_zeros();
+        (index < self.length).then_some(index)
+    }
+
+    ///Returns the minimal length still holding every bit set to BitStorage::ONE, last_set() + 1 or 0 if no bit is set
+    pub fn significant_length(&self) -> usize {
+        self.last_set().map_or(0, |index| index + 1)
+    }
+
+    ///Returns the smallest index greater or equal to *from* holding a bit set to BitStorage::ONE, None if there is none
+    pub fn next_set_bit(&self, from: usize) -> Option<usize> {
+        if from >= self.length {
+            return None;
+        }
+        let first_block = from / T::SIZE;
+        let offset = from % T::SIZE;
+
+        let t = (self.mask.get(first_block)?.clone() >> offset).trailing_zeros();
+        let index = if t != T::SIZE {
+            from + t
+        } else {
+            let (block_index, t) = self
+                .mask
+                .iter()
+                .enumerate()
+                .skip(first_block + 1)
+                .map(|(block_index, block)| (block_index, block.trailing_zeros()))
+                .find(|&(_, t)| t != T::SIZE)?;
+            block_index * T::SIZE + t
+        };
+
+        (index < self.length).then_some(index)
+    }
+
+    ///Returns, for each index within [0:length-1], the distance to the nearest bit set to BitStorage::ONE.
+    ///Every distance is usize::MAX if no bit is set.
+    pub fn distance_transform(&self) -> Vec<usize> {
+        let mut distances = vec![usize::MAX; self.length];
+
+        let mut last_set = None;
+        for (index, distance) in distances.iter_mut().enumerate() {
+            if self.get(index).unwrap_or(false) {
+                last_set = Some(index);
+            }
+            if let Some(last) = last_set {
+                *distance = index - last;
+            }
+        }
+
+        let mut next_set = None;
+        for (index, distance) in distances.iter_mut().enumerate().rev() {
+            if self.get(index).unwrap_or(false) {
+                next_set = Some(index);
+            }
+            if let Some(next) = next_set {
+                *distance = (*distance).min(next - index);
+            }
+        }
+
+        distances
+    }
+
+    ///Returns the smallest power of two sized and aligned range *(start, end)*, end excluded, containing every bit set to BitStorage::ONE.
+    ///The range may end beyond *length*. Returns None if no bit is set.
+    pub fn enclosing_aligned_range(&self) -> Option<(usize, usize)> {
+        let first = self.first_set()?;
+        let last = self.last_set()?;
+
+        let mut size = 1;
+        while first / size != last / size {
+            size <<= 1;
+        }
+        let start = first / size * size;
+
+        Some((start, start + size))
+    }
+
+    ///Returns the number of bits set to BitStorage::ONE whose index *i* verifies i % period == phase.
+    ///Panics if *period* is 0.
+    pub fn match_periodic(&self, period: usize, phase: usize) -> usize {
+        assert!(period != 0, "period must be non-zero");
+        if phase >= period {
+            return 0;
+        }
+        (phase..self.length)
+            .step_by(period)
+            .filter(|&index| self.get(index).unwrap_or(false))
+            .count()
+    }
+
+    ///Returns an iterator over the maximal runs of identical bits as *(value, run length)*, index 0 first
+    pub fn runs(&self) -> impl Iterator<Item = (bool, usize)> + '_ {
+        let mut index = 0;
+        core::iter::from_fn(move || {
+            let value = self.get(index).ok()?;
+            let start = index;
+            while self.get(index).is_ok_and(|v| v == value) {
+                index += 1;
+            }
+            Some((value, index - start))
+        })
+    }
+
+    ///Returns the maximal runs of identical bits as *(value, run length)*, covering the *length* bits index 0 first.
+    ///The inverse of from_run_length()
+    pub fn run_length_encode(&self) -> Vec<(bool, usize)> {
+        self.runs().collect()
+    }
+
+    ///Returns the number of maximal runs of consecutive bits set to BitStorage::ONE
+    pub fn count_runs(&self) -> usize {
+        (0..self.length)
+            .filter(|&index| {
+                self.get(index).unwrap_or(false)
+                    && (index == 0 || !self.get(index - 1).unwrap_or(false))
+            })
+            .count()
+    }
+
+    ///Returns the Shannon entropy, in bits, of the distribution of *(value, run length)* pairs returned by runs().
+    ///The lower it is, the better the mask compresses with a run-length encoding.
+    ///Only available with the "std" feature.
+    #[cfg(feature = "std")]
+    pub fn run_entropy(&self) -> f64 {
+        let mut frequencies: HashMap<(bool, usize), usize> = HashMap::new();
+        let mut total = 0;
+        for run in self.runs() {
+            *frequencies.entry(run).or_default() += 1;
+            total += 1;
+        }
+
+        frequencies
+            .values()
+            .map(|&count| {
+                let p = count as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    ///Returns a FNV-1a hash of the length and of the logical bits, independent of the BitStorage type :
+    ///a `BitMask<u8>` and a `BitMask<u64>` holding the same bits have the same content hash.
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let length_bytes = (self.length as u64).to_le_bytes();
+        let bit_bytes = (0..self.length).step_by(8).map(|start| {
+            (start..(start + 8).min(self.length))
+                .filter(|&index| self.get(index).unwrap_or(false))
+                .fold(0u8, |byte, index| byte | (1 << (index - start)))
+        });
+
+        length_bytes
+            .into_iter()
+            .chain(bit_bytes)
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + Clone + BitAnd<Output = T> + Shl<usize, Output = T> + Sub<Output = T>,
+{
+    ///Returns the number of bits set to BitStorage::ONE strictly below *index*, count_ones() if *index* is beyond *length*
+    pub fn rank(&self, index: usize) -> usize {
+        if index >= self.length {
+            return self.count_ones();
+        }
+        let block_index = index / T::SIZE;
+        let offset = index % T::SIZE;
+
+        let full_blocks: usize = self.mask[..block_index]
+            .iter()
+            .map(|m| m.count_ones())
+            .sum();
+        let partial_block = self.mask.get(block_index).map_or(0, |m| {
+            (m.clone() & ((T::ONE << offset) - T::ONE)).count_ones()
+        });
+
+        full_blocks + partial_block
+    }
+
+    ///Builds a RankIndex over the mask, answering rank() in O(1) and select() in O(log blocks) while the mask is left unmodified
+    pub fn build_rank_index(&self) -> RankIndex<'_, T> {
+        RankIndex::new(self)
+    }
+
+    ///Returns the index of the *n*-th bit set to BitStorage::ONE (starting from 0), None if less than n + 1 bits are set
+    pub fn select(&self, n: usize) -> Option<usize> {
+        let mut remaining = n;
+        for (block_index, block) in self.mask.iter().enumerate() {
+            let ones = block.count_ones();
+            if remaining >= ones {
+                remaining -= ones;
+                continue;
+            }
+
+            let index = block_index * T::SIZE + set_offsets(block.clone()).nth(remaining)?;
+            return (index < self.length).then_some(index);
+        }
+        None
+    }
+
+    ///Returns the indices of every bit set to BitStorage::ONE, in ascending order
+    pub fn to_indices(&self) -> Vec<usize> {
+        let mut indices = Vec::with_capacity(self.count_ones());
+        for (block_index, block) in self.mask.iter().enumerate() {
+            indices.extend(set_offsets(block.clone()).map(|offset| block_index * T::SIZE + offset));
+        }
+        indices
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + Not<Output = T>
+        + BitAnd<Output = T>
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>
+        + Sub<Output = T>,
+{
+    ///Returns a BitStorage unit where the *n* lowest bits are set to BitStorage::ONE
+    fn low_bits(n: usize) -> T {
+        if n >= T::SIZE {
+            !T::ZERO
+        } else {
+            (T::ONE << n) - T::ONE
+        }
+    }
+
+    ///Returns the number of bits set to BitStorage::ONE within *range*, the end of the range being clamped to *length*
+    pub fn count_ones_in_range(&self, range: impl RangeBounds<usize>) -> usize {
+        let (start, end) = clamp_range(range, self.length);
+        if start >= end {
+            return 0;
+        }
+        let first_block = start / T::SIZE;
+        let last_block = (end - 1) / T::SIZE;
+        let head_offset = start % T::SIZE;
+
+        if first_block == last_block {
+            let m = self.mask[first_block].clone() >> head_offset;
+            return (m & Self::low_bits(end - start)).count_ones();
+        }
+
+        let head = (self.mask[first_block].clone() >> head_offset).count_ones();
+        let interior: usize = self.mask[first_block + 1..last_block]
+            .iter()
+            .map(|m| m.count_ones())
+            .sum();
+        let tail = (self.mask[last_block].clone() & Self::low_bits(end - last_block * T::SIZE))
+            .count_ones();
+
+        head + interior + tail
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + BitAndAssign + Shl<usize, Output = T> + Sub<Output = T>,
+{
+    ///Clears the bits of the last BitStorage unit that lie beyond *length*
+    fn clear_padding(&mut self) {
+        let offset = self.length % T::SIZE;
+        if offset != 0 {
+            if let Some(m) = self.mask.get_mut(self.length / T::SIZE) {
+                *m &= (T::ONE << offset) - T::ONE;
+            }
+        }
+    }
+
+    ///Creates a new BitMask of *length* from the big-endian bytes of its BitStorage units, as written by write_be_bytes_into().
+    ///Returns BitMaskError::DeserializationFailed if the bytes can't be split into BitStorage units or hold less than *length* bits
+    pub fn from_be_bytes(bytes: &[u8], length: usize) -> Result<BitMask<T>, BitMaskError> {
+        let mask: Result<Vec<T>, BitMaskError> =
+            bytes.chunks(T::SIZE / 8).map(T::from_be_bytes).collect();
+        Self::from_blocks(mask?, length).map_err(|_| BitMaskError::DeserializationFailed)
+    }
+
+    ///Creates a new BitMask of *length* from the little-endian bytes of its BitStorage units.
+    ///Returns BitMaskError::DeserializationFailed if the bytes can't be split into BitStorage units or hold less than *length* bits
+    pub fn from_le_bytes(bytes: &[u8], length: usize) -> Result<BitMask<T>, BitMaskError> {
+        let mask: Result<Vec<T>, BitMaskError> =
+            bytes.chunks(T::SIZE / 8).map(T::from_le_bytes).collect();
+        Self::from_blocks(mask?, length).map_err(|_| BitMaskError::DeserializationFailed)
+    }
+
+    ///Creates a new BitMask of *length* from its BitStorage units, as returned by as_block_slice(). Extra blocks and every bit at or above *length* are dropped.
+    ///Returns BitMaskError::Overflow if *blocks* hold less than *length* bits
+    pub fn from_blocks(blocks: Vec<T>, length: usize) -> Result<BitMask<T>, BitMaskError> {
+        if length > blocks.len() * T::SIZE {
+            return Err(BitMaskError::Overflow);
+        }
+
+        let mut mask = BitMask {
+            mask: blocks,
+            length,
+        };
+        mask.mask.truncate(length.div_ceil(T::SIZE));
+        mask.clear_padding();
+        Ok(mask)
+    }
+
+    ///Reverses the order of the bytes within each BitStorage unit of the packed representation, for interop with the other endianness.
+    ///Unlike reverse_bits(), the order of the bits within a byte is kept. Only the blocks lying entirely within *length* are swapped,
+    ///a partial last block is left as is so that no bit is moved beyond *length* : swapping twice always restores the mask
+    pub fn swap_byte_order(&mut self) {
+        let whole_blocks = self.length / T::SIZE;
+        for m in self.mask.iter_mut().take(whole_blocks) {
+            let bytes: Vec<u8> = m.to_be_bytes().into_iter().rev().collect();
+            if let Ok(swapped) = T::from_be_bytes(&bytes) {
+                *m = swapped;
+            }
+        }
+        self.clear_padding();
+    }
+
+    ///Clears every bit at or above *length*, including the ones held by BitStorage units beyond the last logical one
+    pub fn normalize(&mut self) {
+        for m in self.mask.iter_mut().skip(self.length.div_ceil(T::SIZE)) {
+            *m = T::ZERO;
+        }
+        self.clear_padding();
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + Not<Output = T>
+        + BitAnd<Output = T>
+        + BitOrAssign
+        + BitAndAssign
+        + Shl<usize, Output = T>
+        + Sub<Output = T>,
+{
+    ///Combines two masks bit by bit using a 2-input truth table.
+    ///Bit *(self_bit << 1) | other_bit* of *lut* gives the resulting bit, so 0b1000 is an AND and 0b0110 a XOR.
+    ///The resulting mask has the length of the longest operand, missing bits being considered as 0.
+    pub fn combine_lut(&self, other: &Self, lut: u8) -> BitMask<T> {
+        let mut res = BitMask::zeros(self.length.max(other.length));
+
+        for (block_index, block) in res.mask.iter_mut().enumerate() {
+            let a = self.mask.get(block_index).map_or(T::ZERO, |b| b.clone());
+            let b = other.mask.get(block_index).map_or(T::ZERO, |b| b.clone());
+
+            for entry in 0..4 {
+                if (lut >> entry) & 1 == 1 {
+                    let a_term = if entry & 2 == 2 {
+                        a.clone()
+                    } else {
+                        !a.clone()
+                    };
+                    let b_term = if entry & 1 == 1 {
+                        b.clone()
+                    } else {
+                        !b.clone()
+                    };
+                    *block |= a_term & b_term;
+                }
+            }
+        }
+
+        res.clear_padding();
+        res
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + PartialEq
+        + Not<Output = T>
+        + BitAnd<Output = T>
+        + BitAndAssign
+        + BitOrAssign
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>,
+{
+    ///Creates a new BitMask of *length* from the low bits of *value*, bit 0 of *value* being at index 0.
+    ///The bits beyond the 64 bits of *value* are set to BitStorage::ZERO
+    pub fn from_u64(value: u64, length: usize) -> BitMask<T> {
+        Self::from_u128(value as u128, length)
+    }
+
+    ///Creates a new BitMask of *length* from the low bits of *value*, bit 0 of *value* being at index 0.
+    ///The bits beyond the 128 bits of *value* are set to BitStorage::ZERO
+    pub fn from_u128(value: u128, length: usize) -> BitMask<T> {
+        let mut mask = BitMask::zeros(length);
+        for index in 0..length.min(u128::BITS as usize) {
+            if (value >> index) & 1 == 1 {
+                let _ = mask.set(index, true);
+            }
+        }
+        mask
+    }
+
+    ///Creates a new BitMask from *(value, run length)* pairs laid one after the other, index 0 first, as returned by run_length_encode().
+    ///The length is the sum of the run lengths
+    pub fn from_run_length(runs: &[(bool, usize)]) -> BitMask<T> {
+        let mut mask = BitMask::zeros(runs.iter().map(|&(_, len)| len).sum());
+        let mut start = 0;
+        for &(value, len) in runs {
+            if value {
+                for index in start..start + len {
+                    let _ = mask.set(index, true);
+                }
+            }
+            start += len;
+        }
+        mask
+    }
+
+    ///Creates a new BitMask of *length* where the bit at index *i* is set iff *i* is a prime number (Sieve of Eratosthenes)
+    pub fn sieve_primes(length: usize) -> BitMask<T> {
+        let mut mask = BitMask::zeros(length);
+        for index in 2..length {
+            let _ = mask.set(index, true);
+        }
+
+        let mut p = 2;
+        while p * p < length {
+            if mask.get(p).unwrap_or(false) {
+                for multiple in (p * p..length).step_by(p) {
+                    let _ = mask.set(multiple, false);
+                }
+            }
+            p += 1;
+        }
+        mask
+    }
+
+    ///Returns the result of one step of the elementary cellular automaton *rule* (0-255) applied to the mask.
+    ///Each output bit is bit *(left << 2) | (center << 1) | right* of *rule*, left being the bit at index i - 1 and the bits beyond the mask being 0.
+    pub fn automaton_step(&self, rule: u8) -> BitMask<T> {
+        let mut res = BitMask::zeros(self.length);
+        for index in 0..self.length {
+            let left = index > 0 && self.get(index - 1).unwrap_or(false);
+            let center = self.get(index).unwrap_or(false);
+            let right = self.get(index + 1).unwrap_or(false);
+            let pattern = ((left as u8) << 2) | ((center as u8) << 1) | (right as u8);
+            if (rule >> pattern) & 1 == 1 {
+                let _ = res.set(index, true);
+            }
+        }
+        res
+    }
+
+    ///Returns self | (self << 1) | ... | (self << amount) computed in a single pass :
+    ///every bit set to BitStorage::ONE is smeared over the *amount* following indices, within *length*
+    pub fn close_left(&self, amount: usize) -> BitMask<T> {
+        let mut res = BitMask::zeros(self.length);
+        let mut last_set: Option<usize> = None;
+        for index in 0..self.length {
+            if self.get(index).unwrap_or(false) {
+                last_set = Some(index);
+            }
+            if last_set.is_some_and(|last| index - last <= amount) {
+                let _ = res.set(index, true);
+            }
+        }
+        res
+    }
+
+    ///Clears every bit set to BitStorage::ONE whose index doesn't satisfy *f*, *f* being called once per bit set only
+    pub fn retain(&mut self, f: impl Fn(usize) -> bool) {
+        let mut next = self.next_set_bit(0);
+        while let Some(index) = next {
+            if !f(index) {
+                let _ = self.set(index, false);
+            }
+            next = self.next_set_bit(index + 1);
+        }
+    }
+
+    ///Sets every bit between the first and the last bits set to BitStorage::ONE, both included
+    pub fn fill_span(&mut self) {
+        if let (Some(first), Some(last)) = (self.first_set(), self.last_set()) {
+            for index in first..=last {
+                let _ = self.set(index, true);
+            }
+        }
+    }
+
+    ///Inserts a bit set to *value* at *index*, every bit from *index* onwards moving one index up and the length growing by 1.
+    ///Returns BitMaskError::IndexOutOfBounds if *index* is greater than *length*
+    pub fn insert(&mut self, index: usize, value: bool) -> Result<(), BitMaskError> {
+        if index > self.length {
+            return Err(BitMaskError::IndexOutOfBounds);
+        }
+        self.grow_to(self.length + 1);
+
+        //Shifting the bits from index onwards up by one, block by block, each block carrying its top bit into the next one.
+        //The bit shifted out of the last block is padding, which is zero, so the padding stays clear
+        let first_block = index / T::SIZE;
+        let low_bits = !(!T::ZERO << (index % T::SIZE));
+        let mut carry = T::ZERO;
+        for block_index in first_block..self.mask.len() {
+            let m = self.mask[block_index].clone();
+            let mut shifted = if block_index == first_block {
+                let mut low = m.clone() & low_bits.clone();
+                low |= (m.clone() & !low_bits.clone()) << 1;
+                low
+            } else {
+                m.clone() << 1
+            };
+            shifted |= carry;
+            carry = m >> (T::SIZE - 1);
+            self.mask[block_index] = shifted;
+        }
+        self.set(index, value)
+    }
+
+    ///Removes and returns the bit at *index*, every bit above it moving one index down and the length shrinking by 1.
+    ///Returns BitMaskError::IndexOutOfBounds if *index* is out of bounds
+    pub fn remove(&mut self, index: usize) -> Result<bool, BitMaskError> {
+        let value = self.get(index)?;
+
+        //Shifting the bits above index down by one, block by block, each block receiving the lowest bit of the next one.
+        //The vacated top bit receives a padding bit, which is zero, so the padding stays clear
+        let first_block = index / T::SIZE;
+        let low_bits = !(!T::ZERO << (index % T::SIZE));
+        for block_index in first_block..self.mask.len() {
+            let m = self.mask[block_index].clone();
+            let mut shifted = if block_index == first_block {
+                let mut low = m.clone() & low_bits.clone();
+                low |= (m >> 1) & !low_bits.clone();
+                low
+            } else {
+                m >> 1
+            };
+            if let Some(next) = self.mask.get(block_index + 1) {
+                shifted |= next.clone() << (T::SIZE - 1);
+            }
+            self.mask[block_index] = shifted;
+        }
+        self.length -= 1;
+        self.mask.truncate(self.length.div_ceil(T::SIZE));
+        Ok(value)
+    }
+
+    ///Reverses the logical order of the bits : the bit at index *i* moves to index length - 1 - i
+    pub fn reverse_bits(&mut self) {
+        for index in 0..self.length / 2 {
+            let _ = self.swap(index, self.length - 1 - index);
+        }
+    }
+
+    ///Exchanges the bits at index *i* and *j*
+    pub fn swap(&mut self, i: usize, j: usize) -> Result<(), BitMaskError> {
+        let value_i = self.get(i)?;
+        let value_j = self.get(j)?;
+        if i != j {
+            self.set(i, value_j)?;
+            self.set(j, value_i)?;
+        }
+        Ok(())
+    }
+
+    ///Returns a mask of the same length where only the bits set to BitStorage::ONE in self whose index appears in the sorted *indices* slice are set
+    pub fn select_set(&self, indices: &[usize]) -> BitMask<T> {
+        let mut res = BitMask::zeros(self.length);
+        for &index in indices.iter().take_while(|&&index| index < self.length) {
+            if self.get(index).unwrap_or(false) {
+                let _ = res.set(index, true);
+            }
+        }
+        res
+    }
+
+    ///Sets bit at *index* to true or false, first growing the mask to index + 1 bits (the new bits being set to BitStorage::ZERO) when *index* is beyond *length*
+    pub fn set_or_grow(&mut self, index: usize, value: bool) {
+        self.grow_to(index + 1);
+        let _ = self.set(index, value);
+    }
+
+    ///Sets every bit whose index is given by *indices*, growing the mask when an index is beyond *length*
+    pub fn union_indices(&mut self, indices: impl IntoIterator<Item = usize>) {
+        for index in indices {
+            self.set_or_grow(index, true);
+        }
+    }
+
+    ///Returns a mask of length ceil(length / chunk) where bit *j* is set if any bit of the *j*-th chunk of self is set.
+    ///Panics if *chunk* is 0.
+    pub fn chunk_or(&self, chunk: usize) -> BitMask<T> {
+        assert!(chunk != 0, "chunk size must be non-zero");
+        let mut res = BitMask::zeros(self.length.div_ceil(chunk));
+        for j in 0..res.length {
+            let start = j * chunk;
+            let end = (start + chunk).min(self.length);
+            if (start..end).any(|index| self.get(index).unwrap_or(false)) {
+                let _ = res.set(j, true);
+            }
+        }
+        res
+    }
+
+    ///Returns a mask of length ceil(length / chunk) where bit *j* is set if every bit of the *j*-th chunk of self is set.
+    ///The last chunk may be shorter than *chunk*, only its bits within *length* are considered.
+    ///Panics if *chunk* is 0.
+    pub fn chunk_and(&self, chunk: usize) -> BitMask<T> {
+        assert!(chunk != 0, "chunk size must be non-zero");
+        let mut res = BitMask::zeros(self.length.div_ceil(chunk));
+        for j in 0..res.length {
+            let start = j * chunk;
+            let end = (start + chunk).min(self.length);
+            if (start..end).all(|index| self.get(index).unwrap_or(false)) {
+                let _ = res.set(j, true);
+            }
+        }
+        res
+    }
+
+    ///Sets every run of at most *max_gap* bits set to BitStorage::ZERO lying between two bits set to BitStorage::ONE
+    pub fn close_gaps(&mut self, max_gap: usize) {
+        let mut last_set: Option<usize> = None;
+        for index in 0..self.length {
+            if !self.get(index).unwrap_or(false) {
+                continue;
+            }
+            if let Some(last) = last_set {
+                if index - last - 1 <= max_gap {
+                    for gap_index in last + 1..index {
+                        let _ = self.set(gap_index, true);
+                    }
+                }
+            }
+            last_set = Some(index);
+        }
+    }
+
+    ///Sets to BitStorage::ZERO every run of bits set to BitStorage::ONE shorter than *min_run*
+    pub fn remove_isolated(&mut self, min_run: usize) {
+        let mut run_start: Option<usize> = None;
+        for index in 0..=self.length {
+            let value = index < self.length && self.get(index).unwrap_or(false);
+            match (run_start, value) {
+                (None, true) => run_start = Some(index),
+                (Some(start), false) => {
+                    if index - start < min_run {
+                        for run_index in start..index {
+                            let _ = self.set(run_index, false);
+                        }
+                    }
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + BitOrAssign
+        + BitAndAssign
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>
+        + Sub<Output = T>,
+{
+    ///Creates a new BitMask of length bits.len() where the bit at index *i* is set iff the *i*-th boolean of *bits* is true
+    pub fn from_bool_slice(bits: &[bool]) -> BitMask<T> {
+        let mut mask = Vec::with_capacity(bits.len().div_ceil(T::SIZE));
+        for chunk in bits.chunks(T::SIZE) {
+            let mut block = T::ZERO;
+            for (offset, &bit) in chunk.iter().enumerate() {
+                if bit {
+                    block |= T::ONE << offset;
+                }
+            }
+            mask.push(block);
+        }
+        BitMask {
+            mask,
+            length: bits.len(),
+        }
+    }
+
+    ///Appends the bits of *other* after the bits of self, the length growing to self.length + other.length
+    pub fn extend_from_mask(&mut self, other: &Self) {
+        let first_block = self.length / T::SIZE;
+        let offset = self.length % T::SIZE;
+
+        self.length += other.length;
+        self.mask.resize(self.length.div_ceil(T::SIZE), T::ZERO);
+
+        for (block_index, block) in other.mask.iter().enumerate() {
+            let target = first_block + block_index;
+            if let Some(m) = self.mask.get_mut(target) {
+                *m |= block.clone() << offset;
+            }
+            if offset != 0 {
+                if let Some(m) = self.mask.get_mut(target + 1) {
+                    *m |= block.clone() >> (T::SIZE - offset);
+                }
+            }
+        }
+    }
+
+    ///Creates a new BitMask made of *count* copies of *pattern* one after the other, of length pattern.length * count
+    pub fn repeat(pattern: &Self, count: usize) -> BitMask<T> {
+        let mut res = BitMask {
+            mask: Vec::with_capacity((pattern.length * count).div_ceil(T::SIZE)),
+            length: 0,
+        };
+        for _ in 0..count {
+            res.extend_from_mask(pattern);
+        }
+        res
+    }
+
+    ///Splits the mask at *index*, returning the bits below *index* and the bits from *index* onwards, re-based to start at 0.
+    ///Returns BitMaskError::IndexOutOfBounds if *index* is greater than *length*
+    pub fn split_at(&self, index: usize) -> Result<(BitMask<T>, BitMask<T>), BitMaskError> {
+        if index > self.length {
+            return Err(BitMaskError::IndexOutOfBounds);
+        }
+
+        let mut low = BitMask {
+            mask: self.mask[..index.div_ceil(T::SIZE)].to_vec(),
+            length: index,
+        };
+        low.clear_padding();
+
+        Ok((low, self.extract(index, self.length - index)))
+    }
+
+    ///Returns the T::SIZE bits starting at *index* packed into one BitStorage unit, the bits beyond the backing blocks being BitStorage::ZERO
+    fn block_at(&self, index: usize) -> T {
+        let source = index / T::SIZE;
+        let offset = index % T::SIZE;
+        let mut block = self
+            .mask
+            .get(source)
+            .map_or(T::ZERO, |m| m.clone() >> offset);
+        if offset != 0 {
+            if let Some(next) = self.mask.get(source + 1) {
+                block |= next.clone() << (T::SIZE - offset);
+            }
+        }
+        block
+    }
+
+    ///Copies the *len* bits starting at *start* into a new mask, re-based to start at 0.
+    ///Only the blocks holding them are read, *start* + *len* must not exceed *length*
+    fn extract(&self, start: usize, len: usize) -> BitMask<T> {
+        let mut res = BitMask::zeros(len);
+        for (block_index, block) in res.mask.iter_mut().enumerate() {
+            *block = self.block_at(start + block_index * T::SIZE);
+        }
+        res.clear_padding();
+        res
+    }
+
+    ///Returns an iterator over successive masks of *n* bits re-based to start at 0, the last one being shorter if *n* doesn't divide *length*.
+    ///Panics if *n* is 0.
+    pub fn chunks(&self, n: usize) -> impl Iterator<Item = BitMask<T>> + '_ {
+        assert!(n != 0, "chunk size must be non-zero");
+        (0..self.length)
+            .step_by(n)
+            .map(move |start| self.extract(start, n.min(self.length - start)))
+    }
+
+    ///Returns an iterator over the length - n + 1 overlapping masks of *n* bits re-based to start at 0, advancing one bit at a time.
+    ///Nothing is yielded if *n* is greater than *length*. Panics if *n* is 0.
+    pub fn windows(&self, n: usize) -> impl Iterator<Item = BitMask<T>> + '_ {
+        assert!(n != 0, "window size must be non-zero");
+        (0..(self.length + 1).saturating_sub(n)).map(move |start| self.extract(start, n))
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + PartialEq
+        + BitAnd<Output = T>
+        + BitOrAssign
+        + BitAndAssign
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>
+        + Sub<Output = T>,
+{
+    ///Returns the lowest index from which the *pattern* bits appear contiguously within self, None if there is no match.
+    ///An empty pattern matches at index 0.
+    pub fn find_pattern(&self, pattern: &BitMask<T>) -> Option<usize> {
+        if pattern.length == 0 {
+            return Some(0);
+        }
+        if pattern.length > self.length {
+            return None;
+        }
+        //The pattern is compared in place, one of its blocks at a time, against the bits of self at each offset
+        let pattern_blocks = &pattern.mask[..pattern.length.div_ceil(T::SIZE)];
+        (0..=self.length - pattern.length).find(|&start| {
+            pattern_blocks
+                .iter()
+                .enumerate()
+                .all(|(block_index, block)| {
+                    let bit = block_index * T::SIZE;
+                    let unused = T::SIZE - (pattern.length - bit).min(T::SIZE);
+                    (self.block_at(start + bit) << unused) >> unused == *block
+                })
+        })
+    }
+
+    ///Shrinks the mask to the bits lying between its first and last bits set to BitStorage::ONE, the first one moving to index 0.
+    ///Returns the original index of the first bit set, so that positions can be restored. An empty mask ends with a length of 0 and returns 0.
+    pub fn crop_to_content(&mut self) -> usize {
+        let (Some(first), Some(last)) = (self.first_set(), self.last_set()) else {
+            *self = BitMask::zeros(0);
+            return 0;
+        };
+        if let Ok((_, content)) = self.split_at(first) {
+            if let Ok((content, _)) = content.split_at(last - first + 1) {
+                *self = content;
+            }
+        }
+        first
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + Clone + Not<Output = T> + BitAndAssign,
+{
+    ///Returns a copy of self where every bit set to BitStorage::ONE in *other* is cleared (self AND NOT other).
+    ///The resulting mask keeps the length of self.
+    pub fn difference(&self, other: &Self) -> BitMask<T> {
+        let mut res = self.clone();
+        for (block, other_block) in res.mask.iter_mut().zip(other.mask.iter()) {
+            *block &= !other_block.clone();
+        }
+        res
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + Clone + PartialEq + Not<Output = T> + BitAnd<Output = T>,
+{
+    ///Returns true if every bit set to BitStorage::ONE in self is also set in *other*
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.mask.iter().enumerate().all(|(block_index, block)| {
+            let other_block = other.mask.get(block_index).map_or(T::ZERO, |b| b.clone());
+            block.clone() & !other_block == T::ZERO
+        })
+    }
+
+    ///Returns the number of bits set to BitStorage::ONE in both self and *other*, without building their intersection
+    pub fn overlap_count(&self, other: &Self) -> usize {
+        self.mask
+            .iter()
+            .zip(other.mask.iter())
+            .map(|(block, other_block)| (block.clone() & other_block.clone()).count_ones())
+            .sum()
+    }
+
+    ///Returns the Jaccard similarity |self ∩ other| / |self ∪ other|, 0.0 if neither mask has a bit set
+    pub fn jaccard(&self, other: &Self) -> f64 {
+        let overlap = self.overlap_count(other);
+        let union = self.count_ones() + other.count_ones() - overlap;
+        if union == 0 {
+            return 0.0;
+        }
+        overlap as f64 / union as f64
+    }
+
+    ///Returns true if every bit set to BitStorage::ONE in *other* is also set in self
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    ///Returns true if self and *other* have no bit set to BitStorage::ONE in common
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.mask
+            .iter()
+            .zip(other.mask.iter())
+            .all(|(block, other_block)| block.clone() & other_block.clone() == T::ZERO)
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + Clone + BitXorAssign,
+{
+    ///Returns the mask of the bits set to BitStorage::ONE in exactly one of self and *other* (self XOR other).
+    ///The resulting mask has the length of the longest operand.
+    pub fn symmetric_difference(&self, other: &Self) -> BitMask<T> {
+        let mut res = self.clone();
+        res ^= other;
+        res
+    }
+}
+
+impl<T> BitMask<T>
+where
+    T: BitStorage + Clone + BitAndAssign + BitOrAssign + BitXorAssign,
 {
-    ///Creates a new BitMask of *size* and fill it with BitStorage::ZERO
-    pub fn zeros(size: usize) -> BitMask<T> {
-        BitMask {
-            mask: vec![T::ZERO; (size / T::SIZE) + 1],
-            length: size,
-        }
+    ///Returns self AND *other*, unlike & which shrinks to the shortest operand.
+    ///Returns BitMaskError::LengthMismatch if both masks don't have the same *length*
+    pub fn try_and(&self, other: &Self) -> Result<BitMask<T>, BitMaskError> {
+        self.check_same_length(other)?;
+        let mut res = self.clone();
+        res &= other;
+        Ok(res)
     }
 
-    ///Returns BitMask's length
-    pub fn length(&self) -> usize {
-        self.length
+    ///Returns self OR *other*, unlike | which grows to the longest operand.
+    ///Returns BitMaskError::LengthMismatch if both masks don't have the same *length*
+    pub fn try_or(&self, other: &Self) -> Result<BitMask<T>, BitMaskError> {
+        self.check_same_length(other)?;
+        let mut res = self.clone();
+        res |= other;
+        Ok(res)
     }
 
-    ///Returns the number of bits set to BitStorage::ONE within the mask
-    pub fn count_ones(&self) -> usize {
-        self.mask.iter().map(|m| m.count_ones()).sum()
+    ///Returns self XOR *other*, unlike ^ which grows to the longest operand.
+    ///Returns BitMaskError::LengthMismatch if both masks don't have the same *length*
+    pub fn try_xor(&self, other: &Self) -> Result<BitMask<T>, BitMaskError> {
+        self.check_same_length(other)?;
+        let mut res = self.clone();
+        res ^= other;
+        Ok(res)
     }
 
-    ///Returns the number trailing BitStorage::ZERO within the mask
-    pub fn trailing_zeros(&self) -> usize {
-        let mut acc = 0;
-        for m in &self.mask {
-            let t = m.trailing_zeros();
-            if t != T::SIZE {
-                return acc + t;
-            }
-            acc += T::SIZE;
+    fn check_same_length(&self, other: &Self) -> Result<(), BitMaskError> {
+        if self.length != other.length {
+            return Err(BitMaskError::LengthMismatch);
         }
-        self.length
+        Ok(())
     }
 }
 
 impl<T> BitMask<T>
 where
     T: BitStorage
-        + Not<Output = T>
         + Clone
-        + BitAndAssign
-        + Shl<usize, Output = T>
+        + PartialEq
+        + BitXor<Output = T>
+        + BitXorAssign
+        + BitAnd<Output = T>
+        + Shr<usize, Output = T>
         + Sub<Output = T>,
 {
-    ///Creates a new BitMask of *size* and fill it with BitStorage::ONE
-    pub fn ones(size: usize) -> BitMask<T> {
-        let mut mask = Self::zeros(size);
-        mask.set_all(true);
-        mask
+    ///Returns the number of bits that differ between self and *other*, the shorter mask being extended with 0.
+    ///Computed block by block, without building the XOR of both masks
+    pub fn hamming_distance(&self, other: &Self) -> usize {
+        (0..self.mask.len().max(other.mask.len()))
+            .map(|block_index| {
+                let a = self.mask.get(block_index).map_or(T::ZERO, |b| b.clone());
+                let b = other.mask.get(block_index).map_or(T::ZERO, |b| b.clone());
+                (a ^ b).count_ones()
+            })
+            .sum()
     }
 
-    ///Sets all the bits to BitStorage::ONE
-    pub fn set_all(&mut self, value: bool) {
-        let s = if value { !T::ZERO } else { T::ZERO };
-        for m in &mut self.mask {
-            *m = s.clone();
+    ///Returns the number of bits that differ between self and *snapshot*, missing bits being considered as 0
+    pub fn dirty_count(&self, snapshot: &Self) -> usize {
+        self.hamming_distance(snapshot)
+    }
+
+    ///Returns the number of leading bits, starting from index 0, that are identical in self and *other*, at most the shortest length
+    pub fn common_prefix_len(&self, other: &Self) -> usize {
+        let length = self.length.min(other.length);
+        for (block_index, (a, b)) in self.mask.iter().zip(other.mask.iter()).enumerate() {
+            let t = (a.clone() ^ b.clone()).trailing_zeros();
+            if t != T::SIZE {
+                return (block_index * T::SIZE + t).min(length);
+            }
         }
+        length
+    }
+
+    ///Returns true if self and *prev* have the same length and differ by exactly one bit, as two successive Gray codes do
+    pub fn is_gray_successor(&self, prev: &Self) -> bool {
+        self.length == prev.length && self.hamming_distance(prev) == 1
+    }
 
-        let last = self.length / T::SIZE;
-        if let Some(m) = self.mask.get_mut(last) {
-            let offset = self.length % T::SIZE;
-            *m &= (T::ONE << offset) - T::ONE;
+    ///Returns the indices of the bits that differ between self and *snapshot*, in ascending order.
+    ///Both masks are walked block by block, the blocks that are identical yielding nothing
+    pub fn dirty_indices(&self, snapshot: &Self) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for block_index in 0..self.mask.len().max(snapshot.mask.len()) {
+            let a = self.mask.get(block_index).map_or(T::ZERO, |b| b.clone());
+            let b = snapshot
+                .mask
+                .get(block_index)
+                .map_or(T::ZERO, |b| b.clone());
+            indices.extend(set_offsets(a ^ b).map(|offset| block_index * T::SIZE + offset));
         }
+        indices
     }
 }
 
-impl<T> BitMask<T>
+///Returns the offsets of the bits set to BitStorage::ONE within *block*, in ascending order, clearing the lowest bit set at each step
+pub(crate) fn set_offsets<T>(mut block: T) -> impl Iterator<Item = usize>
 where
-    T: BitStorage + Not<Output = T> + BitAndAssign + BitOrAssign + Shl<usize, Output = T>,
+    T: BitStorage + Clone + BitAnd<Output = T> + Sub<Output = T>,
 {
-    ///Sets bit at *index* to true or false
-    pub fn set(&mut self, index: usize, value: bool) -> Result<(), BitMaskError> {
-        if index >= self.length {
-            return Err(BitMaskError::IndexOutOfBounds);
+    core::iter::from_fn(move || {
+        let offset = block.trailing_zeros();
+        if offset == T::SIZE {
+            return None;
         }
-        let i = index / T::SIZE;
-        let offset = index % T::SIZE;
+        block = block.clone() & (block.clone() - T::ONE);
+        Some(offset)
+    })
+}
 
-        if let Some(m) = self.mask.get_mut(i) {
-            if value {
-                *m |= T::ONE << offset;
-            } else {
-                *m &= !(T::ONE << offset);
-            }
-            Ok(())
-        } else {
-            Err(BitMaskError::IndexOutOfBounds)
-        }
-    }
+///Converts *range* into a *(start, end)* pair, end excluded and clamped to *length*
+fn clamp_range(range: impl RangeBounds<usize>, length: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.saturating_add(1),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => length,
+    };
+    (start, end.min(length))
 }
 
 impl<T> BitMask<T>
 where
-    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+    T: BitStorage + Not<Output = T> + BitAndAssign + BitOrAssign + Shl<usize, Output = T>,
+    BitMask<T>: ShlAssign<usize> + ShrAssign<usize>,
 {
-    /// Returns a Result that can be :
-    /// - the boolean value of the bit at given index, if the index is within [0:length-1]
-    /// - BitMaskError::IndexOutOfBounds if the index is out of bounds
-    pub fn get(&self, index: usize) -> Result<bool, BitMaskError> {
-        if index >= self.length {
-            return Err(BitMaskError::IndexOutOfBounds);
+    ///Shifts every bit up by one like a shift register, discarding the bit at index length - 1 and inserting *value* at index 0.
+    ///Does nothing on an empty mask
+    pub fn shift_left_in(&mut self, value: bool) {
+        *self <<= 1;
+        let _ = self.set(0, value);
+    }
+
+    ///Shifts every bit down by one like a shift register, discarding the bit at index 0 and inserting *value* at index length - 1.
+    ///Does nothing on an empty mask
+    pub fn shift_right_in(&mut self, value: bool) {
+        *self >>= 1;
+        if let Some(last) = self.length.checked_sub(1) {
+            let _ = self.set(last, value);
         }
-        let i = index / T::SIZE;
-        let offset = index % T::SIZE;
-        self.mask
-            .get(i)
-            .map(|m| (m.clone() >> offset) & T::ONE == T::ONE)
-            .ok_or(BitMaskError::IndexOutOfBounds)
     }
 }
 
@@ -143,14 +1478,127 @@ impl<T: PartialEq> PartialEq for BitMask<T> {
 
 impl<T: PartialEq> Eq for BitMask<T> {}
 
+impl<T, const N: usize> From<[bool; N]> for BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + BitOrAssign
+        + BitAndAssign
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>
+        + Sub<Output = T>,
+{
+    fn from(bits: [bool; N]) -> Self {
+        BitMask::from_bool_slice(&bits)
+    }
+}
+
+impl<T> From<Vec<bool>> for BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + BitOrAssign
+        + BitAndAssign
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>
+        + Sub<Output = T>,
+{
+    fn from(bits: Vec<bool>) -> Self {
+        BitMask::from_bool_slice(&bits)
+    }
+}
+
+///Appends the booleans after the last bit, the length growing by one per boolean
+impl<T> Extend<bool> for BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + PartialEq
+        + Not<Output = T>
+        + BitAnd<Output = T>
+        + BitAndAssign
+        + BitOrAssign
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>,
+{
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        for bit in iter {
+            self.set_or_grow(self.length, bit);
+        }
+    }
+}
+
+///Sets every bit whose index is yielded, growing the mask when an index is beyond *length*
+impl<T> Extend<usize> for BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + PartialEq
+        + Not<Output = T>
+        + BitAnd<Output = T>
+        + BitAndAssign
+        + BitOrAssign
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>,
+{
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        self.union_indices(iter);
+    }
+}
+
+impl<T> FromStr for BitMask<T>
+where
+    T: BitStorage
+        + Clone
+        + BitOrAssign
+        + BitAndAssign
+        + Shl<usize, Output = T>
+        + Shr<usize, Output = T>
+        + Sub<Output = T>,
+{
+    type Err = BitMaskError;
+
+    ///Parses a string of '0' and '1' as written by Display, index 0 first.
+    ///Returns BitMaskError::DeserializationFailed if any other character is found
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bits: Result<Vec<bool>, BitMaskError> = s
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(BitMaskError::DeserializationFailed),
+            })
+            .collect();
+        Ok(BitMask::from_bool_slice(&bits?))
+    }
+}
+
+impl<T> Index<usize> for BitMask<T>
+where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+{
+    type Output = bool;
+
+    ///Returns a reference to a static boolean holding the value of the bit at *index*.
+    ///Panics if *index* is out of bounds, like a Vec would.
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Ok(true) => &true,
+            Ok(false) => &false,
+            Err(_) => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.length, index
+            ),
+        }
+    }
+}
+
 impl<T> BitOrAssign<&Self> for BitMask<T>
 where
     T: BitStorage + Clone + BitOrAssign,
 {
     fn bitor_assign(&mut self, rhs: &Self) {
-        self.mask
-            .resize(self.mask.len().max(rhs.mask.len()), T::ZERO);
-        self.length = self.length.max(rhs.length);
+        self.reconcile_len(self.length.max(rhs.length));
         for block_index in 0..self.mask.len() {
             self.mask[block_index] |= rhs
                 .mask
@@ -173,14 +1621,24 @@ where
     }
 }
 
+impl<T> BitOr<Self> for BitMask<T>
+where
+    for<'a> BitMask<T>: BitOrAssign<&'a BitMask<T>>,
+{
+    type Output = BitMask<T>;
+
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self |= &rhs;
+        self
+    }
+}
+
 impl<T> BitXorAssign<&Self> for BitMask<T>
 where
     T: BitStorage + Clone + BitXorAssign,
 {
     fn bitxor_assign(&mut self, rhs: &Self) {
-        self.mask
-            .resize(self.mask.len().max(rhs.mask.len()), T::ZERO);
-        self.length = self.length.max(rhs.length);
+        self.reconcile_len(self.length.max(rhs.length));
         for block_index in 0..self.mask.len() {
             self.mask[block_index] ^= rhs
                 .mask
@@ -203,11 +1661,24 @@ where
     }
 }
 
+impl<T> BitXor<Self> for BitMask<T>
+where
+    for<'a> BitMask<T>: BitXorAssign<&'a BitMask<T>>,
+{
+    type Output = BitMask<T>;
+
+    fn bitxor(mut self, rhs: Self) -> Self::Output {
+        self ^= &rhs;
+        self
+    }
+}
+
 impl<T> BitAndAssign<&Self> for BitMask<T>
 where
     T: BitStorage + Clone + BitAndAssign,
 {
     fn bitand_assign(&mut self, rhs: &Self) {
+        self.reconcile_len(self.length.min(rhs.length));
         for block_index in 0..self.mask.len() {
             self.mask[block_index] &= rhs
                 .mask
@@ -230,6 +1701,29 @@ where
     }
 }
 
+impl<T> BitAnd<Self> for BitMask<T>
+where
+    for<'a> BitMask<T>: BitAndAssign<&'a BitMask<T>>,
+{
+    type Output = BitMask<T>;
+
+    fn bitand(mut self, rhs: Self) -> Self::Output {
+        self &= &rhs;
+        self
+    }
+}
+
+impl<T> Sub<Self> for &BitMask<T>
+where
+    T: BitStorage + Clone + Not<Output = T> + BitAndAssign,
+{
+    type Output = BitMask<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
 impl<T> Not for &BitMask<T>
 where
     T: BitStorage
@@ -243,47 +1737,38 @@ where
 
     fn not(self) -> Self::Output {
         let mut res = self.clone();
-
-        for m in res.mask.iter_mut() {
-            *m = !m.clone();
-        }
-
-        //Performing a bitwise and on the last BitStorage unit to remove the remaining bits eventually set to 1 by not operator
-        let mask: BitMask<T> = BitMask::ones(res.length % T::SIZE);
-        res.mask[res.length / T::SIZE] &= mask.mask[0].clone();
-
+        res.invert();
         res
     }
 }
 
 impl<T> ShrAssign<usize> for BitMask<T>
 where
-    T: BitStorage + ShrAssign<usize> + ShlAssign<usize> + BitOrAssign + Clone + std::fmt::Debug,
+    T: BitStorage + ShrAssign<usize> + ShlAssign<usize> + BitOrAssign + Clone,
 {
     fn shr_assign(&mut self, rhs: usize) {
-        for index in 0..self.mask.len() {
-            let index_block_to_get_data_from = index + (rhs / T::SIZE);
-
-            let mut block_copy_to_get_data_from = self
-                .mask
-                .get(index_block_to_get_data_from)
-                .unwrap_or(&T::ZERO)
-                .clone();
+        let block_shift = rhs / T::SIZE;
+        let bit_shift = rhs % T::SIZE;
+        let len = self.mask.len();
 
-            let offset_into_block_to_get_data_from = rhs % T::SIZE;
-
-            block_copy_to_get_data_from >>= offset_into_block_to_get_data_from;
-
-            let mut next_block_copy_to_get_data_from = self
-                .mask
-                .get(index_block_to_get_data_from + 1)
-                .unwrap_or(&T::ZERO)
-                .clone();
-
-            next_block_copy_to_get_data_from <<= T::SIZE - (rhs % T::SIZE);
-            block_copy_to_get_data_from |= next_block_copy_to_get_data_from;
+        //Moving whole blocks first
+        if block_shift >= len {
+            self.mask.fill(T::ZERO);
+            return;
+        }
+        self.mask.rotate_left(block_shift);
+        self.mask[len - block_shift..].fill(T::ZERO);
 
-            self.mask[index] = block_copy_to_get_data_from;
+        //Then shifting the remaining bits across blocks
+        if bit_shift != 0 {
+            for index in 0..len {
+                self.mask[index] >>= bit_shift;
+                if let Some(next) = self.mask.get(index + 1) {
+                    let mut carry = next.clone();
+                    carry <<= T::SIZE - bit_shift;
+                    self.mask[index] |= carry;
+                }
+            }
         }
     }
 }
@@ -303,27 +1788,42 @@ where
 
 impl<T> ShlAssign<usize> for BitMask<T>
 where
-    T: BitStorage + ShlAssign<usize> + ShrAssign<usize> + BitOrAssign + Clone + std::fmt::Debug,
+    T: BitStorage
+        + ShlAssign<usize>
+        + ShrAssign<usize>
+        + BitOrAssign
+        + BitAndAssign
+        + Clone
+        + Shl<usize, Output = T>
+        + Sub<Output = T>,
 {
     fn shl_assign(&mut self, rhs: usize) {
-        for index in (0..self.mask.len()).rev() {
-            if rhs >= T::SIZE {
-                self.mask[index] = T::ZERO;
-            } else {
-                self.mask[index] <<= rhs;
-            }
-
-            let block_to_shift_index = index as isize - (rhs / T::SIZE) as isize;
+        let block_shift = rhs / T::SIZE;
+        let bit_shift = rhs % T::SIZE;
+        let len = self.mask.len();
 
-            let mut block_to_shift_val = self
-                .mask
-                .get(block_to_shift_index as usize)
-                .unwrap_or(&T::ZERO)
-                .clone();
-            block_to_shift_val >>= T::SIZE - (rhs % T::SIZE);
+        //Moving whole blocks first
+        if block_shift >= len {
+            self.mask.fill(T::ZERO);
+            return;
+        }
+        self.mask.rotate_right(block_shift);
+        self.mask[..block_shift].fill(T::ZERO);
 
-            self.mask[index] |= block_to_shift_val;
+        //Then shifting the remaining bits across blocks
+        if bit_shift != 0 {
+            for index in (0..len).rev() {
+                self.mask[index] <<= bit_shift;
+                if index > 0 {
+                    let mut carry = self.mask[index - 1].clone();
+                    carry >>= T::SIZE - bit_shift;
+                    self.mask[index] |= carry;
+                }
+            }
         }
+
+        //Discarding the bits shifted beyond length
+        self.clear_padding();
     }
 }
 
@@ -340,14 +1840,101 @@ where
     }
 }
 
-impl<T: BitStorage + Display + Binary> Display for BitMask<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
+impl<T> Display for BitMask<T>
+where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut s = String::with_capacity(self.length);
 
-        let mut rem = self.length as isize;
+        let mut rem = self.length;
         for m in &self.mask {
-            let size = rem.min(T::SIZE as isize) as usize;
+            for offset in 0..rem.min(T::SIZE) {
+                s.push(if (m.clone() >> offset) & T::ONE == T::ONE {
+                    '1'
+                } else {
+                    '0'
+                });
+            }
+            rem = rem.saturating_sub(T::SIZE);
+        }
+
+        f.write_str(&s)
+    }
+}
+
+///Shows the logical bits as the Display string, index 0 first, e.g. BitMask { bits: "01100", length: 5 }
+impl<T> Debug for BitMask<T>
+where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BitMask")
+            .field("bits", &self.to_string())
+            .field("length", &self.length)
+            .finish()
+    }
+}
+
+impl<T> Binary for BitMask<T>
+where
+    T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+{
+    ///Writes the same string as Display, prefixed by 0b with the alternate flag
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            f.write_str("0b")?;
+        }
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{format, string::ToString};
+
+    #[test]
+    fn test_zeros_block_count() {
+        assert_eq!(BitMask::<u64>::zeros(0).mask.len(), 0);
+        assert_eq!(BitMask::<u64>::zeros(1).mask.len(), 1);
+        assert_eq!(BitMask::<u64>::zeros(64).mask.len(), 1);
+        assert_eq!(BitMask::<u64>::zeros(65).mask.len(), 2);
+        assert_eq!(BitMask::<u8>::zeros(16).mask.len(), 2);
+        assert_eq!(BitMask::<u8>::zeros(17).mask.len(), 3);
+    }
+
+    #[test]
+    fn test_block_aligned_length() {
+        let mask: BitMask<u64> = BitMask::ones(64);
+        assert_eq!(mask.count_ones(), 64);
+        assert_eq!(!&mask, BitMask::zeros(64));
+
+        let mask: BitMask<u8> = BitMask::zeros(0);
+        assert_eq!(!&mask, BitMask::ones(0));
+        assert_eq!(mask.to_string(), "".to_string());
+    }
+
+    #[test]
+    fn test_owned_operators_reuse_allocation() {
+        let a: BitMask<u8> = BitMask::ones(20);
+        let ptr = a.mask.as_ptr();
+        let res = a | BitMask::zeros(20);
+        assert_eq!(res.mask.as_ptr(), ptr);
 
+        let res = res & BitMask::ones(20);
+        assert_eq!(res.mask.as_ptr(), ptr);
+
+        let res = res ^ BitMask::ones(20);
+        assert_eq!(res.mask.as_ptr(), ptr);
+    }
+
+    ///Display implementation formatting each BitStorage unit with format!, used as a reference
+    fn reference_display<T: BitStorage + Binary>(mask: &BitMask<T>) -> String {
+        let mut s = String::new();
+        let mut rem = mask.length as isize;
+        for m in &mask.mask {
+            let size = rem.min(T::SIZE as isize) as usize;
             s.push_str(
                 &format!("{:#0w$b}", m, w = T::SIZE + 2)[(T::SIZE + 2 - size)..]
                     .chars()
@@ -356,7 +1943,93 @@ impl<T: BitStorage + Display + Binary> Display for BitMask<T> {
             );
             rem -= T::SIZE as isize;
         }
+        s
+    }
+
+    #[test]
+    fn test_display_large_mask() {
+        let mut mask: BitMask<u64> = BitMask::zeros(10_000);
+        let mut small: BitMask<u8> = BitMask::zeros(10_000);
+        for index in (0..10_000).filter(|index| index % 3 == 0 || index % 7 == 0) {
+            mask.set(index, true).unwrap();
+            small.set(index, true).unwrap();
+        }
+
+        assert_eq!(mask.to_string(), reference_display(&mask));
+        assert_eq!(small.to_string(), reference_display(&small));
+        assert_eq!(mask.to_string(), small.to_string());
+        assert_eq!(mask.to_string().len(), 10_000);
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        //A 40-bit mask shortened to 10 bits, still backed by its 5 blocks
+        let mut mask: BitMask<u8> = BitMask {
+            mask: vec![0b1000_0001, 0b0000_0010, 0, 0, 0],
+            length: 10,
+        };
+        assert_eq!(mask.block_count(), 5);
+        mask.shrink_to_fit();
+        assert_eq!(mask.block_count(), 2);
+        assert!(mask.mask.capacity() < 5);
+        assert_eq!(mask.to_string(), "1000000101");
+
+        mask.shrink_to_fit();
+        assert_eq!(mask.block_count(), 2);
+
+        let mut empty: BitMask<u64> = BitMask {
+            mask: vec![0; 3],
+            length: 0,
+        };
+        empty.shrink_to_fit();
+        assert_eq!(empty.block_count(), 0);
+    }
+
+    #[test]
+    fn test_set_all_over_allocated() {
+        let mut mask: BitMask<u64> = BitMask {
+            mask: vec![0; 3],
+            length: 64,
+        };
+        mask.set_all(true);
+        assert_eq!(mask.count_ones(), 64);
+        assert_eq!(mask.mask, vec![u64::MAX, 0, 0]);
+
+        let mut mask: BitMask<u8> = BitMask {
+            mask: vec![0; 4],
+            length: 12,
+        };
+        mask.set_all(true);
+        assert_eq!(mask.count_ones(), 12);
+        assert_eq!(mask.mask, vec![0xFF, 0x0F, 0, 0]);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut mask: BitMask<u8> = BitMask {
+            mask: vec![0xFF, 0xFF, 0xFF],
+            length: 10,
+        };
+        assert_eq!(mask.count_ones(), 24);
+
+        mask.normalize();
+        assert_eq!(mask.count_ones(), 10);
+        assert_eq!(mask.mask, vec![0xFF, 0x03, 0]);
+        assert_eq!(mask.to_string(), "1111111111".to_string());
+    }
+
+    #[test]
+    fn test_invert_in_place() {
+        let mut mask: BitMask<u8> = BitMask::ones(20);
+        let ptr = mask.mask.as_ptr();
+
+        mask.invert();
+        assert_eq!(mask, BitMask::zeros(20));
+        assert_eq!(mask.mask.as_ptr(), ptr);
 
-        write!(f, "{}", s)
+        mask.set(3, true).unwrap();
+        mask.invert();
+        assert_eq!(mask.count_ones(), 19);
+        assert_eq!(mask, !&BitMask::from_u64(1 << 3, 20));
     }
 }
@@ -0,0 +1,80 @@
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::ops::{BitAnd, Shl, Sub};
+
+use crate::bit_storage::BitStorage;
+use crate::{set_offsets, BitMask};
+
+///Succinct rank/select index over a borrowed BitMask, returned by BitMask::build_rank_index().
+///It stores the number of bits set before each block, making rank() O(1) and select() O(log blocks).
+///The mask can't be mutated while the index borrows it, so the cumulative counts never go stale
+#[derive(Clone)]
+pub struct RankIndex<'a, T> {
+    mask: &'a BitMask<T>,
+    //cumulative[i] is the number of bits set within the blocks [0:i-1], the last entry being count_ones()
+    cumulative: Vec<usize>,
+}
+
+impl<T> Debug for RankIndex<'_, T>
+where
+    BitMask<T>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RankIndex")
+            .field("mask", self.mask)
+            .field("cumulative", &self.cumulative)
+            .finish()
+    }
+}
+
+impl<'a, T> RankIndex<'a, T>
+where
+    T: BitStorage + Clone + BitAnd<Output = T> + Shl<usize, Output = T> + Sub<Output = T>,
+{
+    pub(crate) fn new(mask: &'a BitMask<T>) -> RankIndex<'a, T> {
+        let mut cumulative = Vec::with_capacity(mask.mask.len() + 1);
+        let mut acc = 0;
+        cumulative.push(acc);
+        for m in &mask.mask {
+            acc += m.count_ones();
+            cumulative.push(acc);
+        }
+        RankIndex { mask, cumulative }
+    }
+
+    ///Returns the number of bits set to BitStorage::ONE within the indexed mask
+    pub fn count_ones(&self) -> usize {
+        self.cumulative.last().copied().unwrap_or(0)
+    }
+
+    ///Returns the number of bits set to BitStorage::ONE strictly below *index*, count_ones() if *index* is beyond *length*.
+    ///Same result as BitMask::rank(), in O(1)
+    pub fn rank(&self, index: usize) -> usize {
+        if index >= self.mask.length {
+            return self.count_ones();
+        }
+        let block_index = index / T::SIZE;
+        let offset = index % T::SIZE;
+
+        let partial_block = self.mask.mask.get(block_index).map_or(0, |m| {
+            (m.clone() & ((T::ONE << offset) - T::ONE)).count_ones()
+        });
+
+        self.cumulative[block_index] + partial_block
+    }
+
+    ///Returns the index of the *n*-th bit set to BitStorage::ONE (starting from 0), None if less than n + 1 bits are set.
+    ///Same result as BitMask::select(), the block holding the bit being found by binary search
+    pub fn select(&self, n: usize) -> Option<usize> {
+        if n >= self.count_ones() {
+            return None;
+        }
+        //Last block whose preceding blocks hold at most n bits set, it holds the n-th one
+        let block_index = self.cumulative.partition_point(|&ones| ones <= n) - 1;
+
+        let block = self.mask.mask.get(block_index)?.clone();
+        let index =
+            block_index * T::SIZE + set_offsets(block).nth(n - self.cumulative[block_index])?;
+        (index < self.mask.length).then_some(index)
+    }
+}
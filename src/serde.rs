@@ -1,3 +1,5 @@
+use std::ops::{BitAndAssign, Shl, Sub};
+
 use base64::{decode, encode};
 use serde::de::Error;
 use serde::{Deserialize, Serialize};
@@ -13,20 +15,20 @@ struct BitMaskSerializable {
     length: usize,
 }
 
+///Returns the big-endian bytes of every BitStorage unit of the mask
+fn block_bytes<T: BitStorage>(value: &BitMask<T>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.write_be_bytes_into(&mut bytes);
+    bytes
+}
+
 impl<T> From<&BitMask<T>> for BitMaskSerializable
 where
     T: BitStorage,
 {
     fn from(value: &BitMask<T>) -> Self {
-        let mut bytes = Vec::new();
-
-        value
-            .mask
-            .iter()
-            .for_each(|e| bytes.append(&mut e.to_be_bytes()));
-
         Self {
-            mask: encode(bytes),
+            mask: encode(block_bytes(value)),
             length: value.length,
         }
     }
@@ -34,25 +36,17 @@ where
 
 impl<T> TryFrom<BitMaskSerializable> for BitMask<T>
 where
-    T: BitStorage,
+    T: BitStorage + BitAndAssign + Shl<usize, Output = T> + Sub<Output = T>,
 {
     type Error = BitMaskError;
 
     fn try_from(value: BitMaskSerializable) -> Result<Self, Self::Error> {
         let bytes = decode(value.mask).map_err(|_| BitMaskError::DeserializationFailed)?;
-
-        let mask: Result<Vec<T>, BitMaskError> = bytes
-            .chunks(T::SIZE / 8)
-            .map(|e| T::from_be_bytes(e))
-            .collect();
-
-        Ok(Self {
-            mask: mask?,
-            length: value.length,
-        })
+        BitMask::from_be_bytes(&bytes, value.length)
     }
 }
 
+///Each BitStorage unit is serialized as its big-endian bytes : a serialized `BitMask<usize>` is not portable between 32 and 64 bits targets.
 impl<T> Serialize for BitMask<T>
 where
     T: BitStorage + Serialize,
@@ -68,7 +62,7 @@ where
 
 impl<'de, T> Deserialize<'de> for BitMask<T>
 where
-    T: BitStorage,
+    T: BitStorage + BitAndAssign + Shl<usize, Output = T> + Sub<Output = T>,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -79,6 +73,172 @@ where
     }
 }
 
+///Compact serialization for binary formats such as bincode, to be used with `#[serde(with = "nbitmask::serde::compact")]`.
+///The BitStorage units are written as raw bytes instead of a base64 string, and the length as a u64.
+pub mod compact {
+    use std::ops::{BitAndAssign, Shl, Sub};
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::block_bytes;
+    use crate::bit_storage::BitStorage;
+    use crate::BitMask;
+
+    #[derive(Serialize, Deserialize)]
+    struct BitMaskCompact {
+        #[serde(with = "serde_bytes")]
+        mask: Vec<u8>,
+        length: u64,
+    }
+
+    ///Serializes the mask as raw bytes followed by its length
+    pub fn serialize<T, S>(value: &BitMask<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: BitStorage,
+        S: Serializer,
+    {
+        BitMaskCompact {
+            mask: block_bytes(value),
+            length: value.length as u64,
+        }
+        .serialize(serializer)
+    }
+
+    ///Deserializes a mask written by serialize()
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<BitMask<T>, D::Error>
+    where
+        T: BitStorage + BitAndAssign + Shl<usize, Output = T> + Sub<Output = T>,
+        D: Deserializer<'de>,
+    {
+        let compact = BitMaskCompact::deserialize(deserializer)?;
+        let length = usize::try_from(compact.length).map_err(Error::custom)?;
+        BitMask::from_be_bytes(&compact.mask, length).map_err(Error::custom)
+    }
+}
+
+///Packed serialization for non self-describing formats such as bincode, to be used with `#[serde(with = "nbitmask::serde::packed")]`.
+///The length is written as a u64 followed by exactly ceil(length / 8) raw bytes, byte *k* holding the bits 8k to 8k + 7,
+///without any length prefix : the representation doesn't depend on the BitStorage type.
+pub mod packed {
+    use std::fmt;
+    use std::marker::PhantomData;
+    use std::ops::{BitAndAssign, Shl, Sub};
+
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::ser::SerializeTuple;
+    use serde::{Deserializer, Serializer};
+
+    use crate::bit_storage::BitStorage;
+    use crate::error::BitMaskError;
+    use crate::BitMask;
+
+    ///Serializes the mask as its length followed by its logical bytes
+    pub fn serialize<T, S>(value: &BitMask<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: BitStorage,
+        S: Serializer,
+    {
+        let byte_count = value.length.div_ceil(8);
+        let bytes = value.mask.iter().flat_map(|m| m.to_le_bytes());
+
+        let mut tuple = serializer.serialize_tuple(1 + byte_count)?;
+        tuple.serialize_element(&(value.length as u64))?;
+        for byte in bytes.take(byte_count) {
+            tuple.serialize_element(&byte)?;
+        }
+        tuple.end()
+    }
+
+    struct PackedVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for PackedVisitor<T>
+    where
+        T: BitStorage + BitAndAssign + Shl<usize, Output = T> + Sub<Output = T>,
+    {
+        type Value = BitMask<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a length followed by ceil(length / 8) bytes")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let length: u64 = seq
+                .next_element()?
+                .ok_or_else(|| Error::invalid_length(0, &self))?;
+            let length = usize::try_from(length).map_err(Error::custom)?;
+
+            //The length comes from the payload : the bytes are pushed as they arrive instead of being allocated upfront,
+            //so a huge length with a short body fails on the missing bytes rather than on the allocation
+            let byte_count = length.div_ceil(8);
+            let mut bytes = Vec::new();
+            for index in 0..byte_count {
+                let byte: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| Error::invalid_length(index + 1, &self))?;
+                bytes.push(byte);
+            }
+            let padded_byte_count = length
+                .div_ceil(T::SIZE)
+                .checked_mul(T::SIZE / 8)
+                .ok_or_else(|| Error::custom(BitMaskError::Overflow))?;
+            bytes.resize(padded_byte_count, 0);
+
+            BitMask::from_le_bytes(&bytes, length).map_err(Error::custom)
+        }
+    }
+
+    ///Deserializes a mask written by serialize()
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<BitMask<T>, D::Error>
+    where
+        T: BitStorage + BitAndAssign + Shl<usize, Output = T> + Sub<Output = T>,
+        D: Deserializer<'de>,
+    {
+        //The number of bytes is only known once the length is read
+        deserializer.deserialize_tuple(usize::MAX, PackedVisitor(PhantomData))
+    }
+}
+
+///Human readable serialization as the string written by Display, to be used with `#[serde(with = "nbitmask::serde::bit_string")]`.
+///The length of the mask is the length of the string.
+pub mod bit_string {
+    use std::ops::{BitAnd, BitAndAssign, BitOrAssign, Shl, Shr, Sub};
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::bit_storage::BitStorage;
+    use crate::BitMask;
+
+    ///Serializes the mask as a string of '0' and '1', index 0 first
+    pub fn serialize<T, S>(value: &BitMask<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: BitStorage + BitAnd<Output = T> + Clone + PartialEq + Shr<usize, Output = T>,
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    ///Deserializes a mask written by serialize()
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<BitMask<T>, D::Error>
+    where
+        T: BitStorage
+            + Clone
+            + BitOrAssign
+            + BitAndAssign
+            + Shl<usize, Output = T>
+            + Shr<usize, Output = T>
+            + Sub<Output = T>,
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,4 +258,97 @@ mod tests {
 
         assert_eq!(mask2, mask);
     }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Compact(#[serde(with = "super::compact")] BitMask<u8>);
+
+    #[test]
+    fn test_compact_bincode() {
+        let mut mask: BitMask<u8> = BitMask::zeros(1000);
+        mask.set(0, true).unwrap();
+        mask.set(500, true).unwrap();
+        mask.set(999, true).unwrap();
+
+        let bytes = bincode::serialize(&Compact(mask.clone())).unwrap();
+        //mask bytes + their length prefix + the length
+        assert_eq!(bytes.len(), 1000 / 8 + 8 + 8);
+
+        let Compact(mask2) = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(mask2, mask);
+
+        assert!(bytes.len() < bincode::serialize(&mask).unwrap().len());
+    }
+
+    #[test]
+    fn test_deserialize_length_too_big() {
+        let res: Result<BitMask<u8>, _> =
+            serde_json::from_str("{\"mask\":\"AQ==\",\"length\":1000}");
+        assert!(res.is_err());
+
+        let res: Result<BitMask<u8>, _> = serde_json::from_str("{\"mask\":\"AQ==\",\"length\":8}");
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_clears_padding() {
+        //0xFF 0xFF with a length of 3 : bits 3 to 15 are stray
+        let mask: BitMask<u8> = serde_json::from_str("{\"mask\":\"//8=\",\"length\":3}").unwrap();
+        assert_eq!(mask.count_ones(), 3);
+        assert_eq!(mask, BitMask::ones(3));
+        assert_eq!(!&mask, BitMask::zeros(3));
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct BitString(#[serde(with = "super::bit_string")] BitMask<u8>);
+
+    #[test]
+    fn test_bit_string_json() {
+        let mut mask: BitMask<u8> = BitMask::zeros(5);
+        mask.set(1, true).unwrap();
+
+        let json = serde_json::to_string(&BitString(mask.clone())).unwrap();
+        assert_eq!(json, "\"01000\"");
+
+        let BitString(mask2) = serde_json::from_str(&json).unwrap();
+        assert_eq!(mask2, mask);
+
+        let res: Result<BitString, _> = serde_json::from_str("\"01200\"");
+        assert!(res.is_err());
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Packed(#[serde(with = "super::packed")] BitMask<u64>);
+
+    #[test]
+    fn test_packed_bincode() {
+        let mut mask: BitMask<u64> = BitMask::zeros(1001);
+        mask.set(0, true).unwrap();
+        mask.set(500, true).unwrap();
+        mask.set(1000, true).unwrap();
+
+        let bytes = bincode::serialize(&Packed(mask.clone())).unwrap();
+        //the length + ceil(1001 / 8) bytes
+        assert_eq!(bytes.len(), 8 + 126);
+
+        let Packed(mask2) = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(mask2, mask);
+
+        let Packed(empty) =
+            bincode::deserialize(&bincode::serialize(&Packed(BitMask::zeros(0))).unwrap()).unwrap();
+        assert_eq!(empty, BitMask::zeros(0));
+
+        assert!(bincode::deserialize::<Packed>(&bytes[..100]).is_err());
+    }
+
+    #[test]
+    fn test_packed_huge_length() {
+        //A length of 2^50 bits followed by a single byte
+        let mut bytes = (1u64 << 50).to_le_bytes().to_vec();
+        bytes.push(0xFF);
+        assert!(bincode::deserialize::<Packed>(&bytes).is_err());
+
+        let mut bytes = u64::MAX.to_le_bytes().to_vec();
+        bytes.push(0xFF);
+        assert!(bincode::deserialize::<Packed>(&bytes).is_err());
+    }
 }
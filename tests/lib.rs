@@ -1,5 +1,17 @@
+use nbitmask::bit_storage::BitStorage;
+use nbitmask::cached::CachedBitMask;
 use nbitmask::BitMask;
 
+///Linear congruential generator starting from *seed*, to get reproducible pseudo-random values without a new dependency
+fn lcg(mut seed: u64) -> impl FnMut() -> u64 {
+    move || {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        seed >> 33
+    }
+}
+
 #[test]
 fn test_print() {
     let mask: BitMask<u64> = BitMask::zeros(5);
@@ -286,3 +298,1705 @@ fn test_example2() {
     assert_eq!(mask.to_string(), mask2.to_string());
     assert_eq!(mask, mask2);
 }
+
+#[test]
+fn test_combine_lut() {
+    let mut a: BitMask<u8> = BitMask::zeros(12);
+    let mut b: BitMask<u8> = BitMask::zeros(12);
+    a.set(0, true).unwrap();
+    a.set(1, true).unwrap();
+    a.set(9, true).unwrap();
+    b.set(1, true).unwrap();
+    b.set(2, true).unwrap();
+    b.set(9, true).unwrap();
+
+    assert_eq!(a.combine_lut(&b, 0b1000), &a & &b);
+    assert_eq!(a.combine_lut(&b, 0b0110), &a ^ &b);
+    assert_eq!(a.combine_lut(&b, 0b0001), !&(&a | &b));
+}
+
+#[test]
+fn test_block_count() {
+    for length in [0, 1, 7, 8, 9, 63, 64, 65, 128, 200] {
+        let mask: BitMask<u8> = BitMask::zeros(length);
+        assert_eq!(mask.block_count(), length.div_ceil(8));
+        assert_eq!(mask.byte_len(), length.div_ceil(8));
+
+        let mask: BitMask<u64> = BitMask::ones(length);
+        assert_eq!(mask.block_count(), length.div_ceil(64));
+        assert_eq!(mask.byte_len(), length.div_ceil(64) * 8);
+    }
+}
+
+#[test]
+fn test_distance_transform() {
+    let mut mask: BitMask<u8> = BitMask::zeros(12);
+    mask.set(2, true).unwrap();
+    mask.set(9, true).unwrap();
+
+    assert_eq!(
+        mask.distance_transform(),
+        vec![2, 1, 0, 1, 2, 3, 3, 2, 1, 0, 1, 2]
+    );
+
+    let empty: BitMask<u8> = BitMask::zeros(3);
+    assert_eq!(empty.distance_transform(), vec![usize::MAX; 3]);
+}
+
+#[test]
+fn test_get_set_usize() {
+    let mut mask: BitMask<usize> = BitMask::zeros(70);
+    mask.set(1, true).unwrap();
+    mask.set(69, true).unwrap();
+    assert!(!mask.get(0).unwrap());
+    assert!(mask.get(1).unwrap());
+    assert!(mask.get(69).unwrap());
+    assert_eq!(mask.count_ones(), 2);
+    assert!(mask.set(70, true).is_err());
+}
+
+#[test]
+fn test_enclosing_aligned_range() {
+    let mut mask: BitMask<u8> = BitMask::zeros(20);
+    assert_eq!(mask.enclosing_aligned_range(), None);
+
+    mask.set(3, true).unwrap();
+    mask.set(5, true).unwrap();
+    assert_eq!(mask.enclosing_aligned_range(), Some((0, 8)));
+
+    let mut mask: BitMask<u8> = BitMask::zeros(20);
+    mask.set(12, true).unwrap();
+    mask.set(13, true).unwrap();
+    assert_eq!(mask.enclosing_aligned_range(), Some((12, 14)));
+
+    mask.set(17, true).unwrap();
+    assert_eq!(mask.enclosing_aligned_range(), Some((0, 32)));
+}
+
+#[test]
+fn test_owned_operators() {
+    let mut a: BitMask<u64> = BitMask::zeros(3);
+    let mut b: BitMask<u64> = BitMask::zeros(3);
+    a.set(0, true).unwrap();
+    a.set(1, true).unwrap();
+    b.set(1, true).unwrap();
+    b.set(2, true).unwrap();
+
+    assert_eq!((a.clone() | b.clone()).to_string(), "111".to_string());
+    assert_eq!((a.clone() & b.clone()).to_string(), "010".to_string());
+    assert_eq!((a.clone() ^ b.clone()).to_string(), "101".to_string());
+    assert_eq!(a.clone() ^ b.clone(), &a ^ &b);
+}
+
+#[test]
+fn test_difference() {
+    let mut a: BitMask<u8> = BitMask::zeros(4);
+    a.set(0, true).unwrap();
+    a.set(1, true).unwrap();
+    a.set(2, true).unwrap();
+
+    let mut b: BitMask<u8> = BitMask::zeros(4);
+    b.set(1, true).unwrap();
+    b.set(2, true).unwrap();
+
+    assert_eq!(a.difference(&b).to_string(), "1000".to_string());
+    assert_eq!(&a - &b, a.difference(&b));
+
+    let c: BitMask<u8> = BitMask::ones(20);
+    assert_eq!(&a - &c, BitMask::zeros(4));
+    assert_eq!((&c - &a).length(), 20);
+}
+
+#[test]
+fn test_close_gaps() {
+    let mut mask: BitMask<u8> = BitMask::zeros(12);
+    mask.set(0, true).unwrap();
+    mask.set(2, true).unwrap();
+    mask.set(3, true).unwrap();
+    mask.set(7, true).unwrap();
+    mask.set(11, true).unwrap();
+
+    mask.close_gaps(1);
+    assert_eq!(mask.to_string(), "111100010001".to_string());
+
+    mask.close_gaps(3);
+    assert_eq!(mask.to_string(), "111111111111".to_string());
+}
+
+#[test]
+fn test_subset_superset_disjoint() {
+    let mut a: BitMask<u8> = BitMask::zeros(5);
+    a.set(1, true).unwrap();
+    a.set(4, true).unwrap();
+
+    let mut b: BitMask<u8> = BitMask::zeros(20);
+    b.set(1, true).unwrap();
+    b.set(4, true).unwrap();
+    b.set(15, true).unwrap();
+
+    assert!(a.is_subset(&b));
+    assert!(!b.is_subset(&a));
+    assert!(b.is_superset(&a));
+    assert!(a.is_subset(&a));
+    assert!(!a.is_disjoint(&b));
+
+    let mut c: BitMask<u8> = BitMask::zeros(20);
+    c.set(0, true).unwrap();
+    c.set(10, true).unwrap();
+
+    assert!(a.is_disjoint(&c));
+    assert!(c.is_disjoint(&a));
+    assert!(!c.is_subset(&b));
+}
+
+#[test]
+fn test_remove_isolated() {
+    let mut mask: BitMask<u8> = BitMask::zeros(12);
+    mask.set(0, true).unwrap();
+    mask.set(3, true).unwrap();
+    mask.set(4, true).unwrap();
+    mask.set(5, true).unwrap();
+    mask.set(8, true).unwrap();
+    mask.set(11, true).unwrap();
+
+    mask.remove_isolated(2);
+    assert_eq!(mask.to_string(), "000111000000".to_string());
+
+    mask.remove_isolated(4);
+    assert_eq!(mask, BitMask::zeros(12));
+}
+
+#[test]
+fn test_symmetric_difference() {
+    let mut a: BitMask<u8> = BitMask::zeros(3);
+    a.set_all(true);
+    assert_eq!(a.symmetric_difference(&BitMask::ones(3)), BitMask::zeros(3));
+
+    let c = a.symmetric_difference(&BitMask::zeros(5));
+    assert_eq!(c.to_string(), "11100".to_string());
+
+    let d = a.symmetric_difference(&BitMask::ones(6));
+    assert_eq!(d.to_string(), "000111".to_string());
+    assert_eq!(d, &a ^ &BitMask::ones(6));
+}
+
+#[test]
+fn test_get_unchecked() {
+    let mut mask: BitMask<u16> = BitMask::zeros(1000);
+    for index in (0..1000).step_by(7) {
+        mask.set(index, true).unwrap();
+    }
+
+    for index in 0..mask.length() {
+        assert_eq!(
+            unsafe { mask.get_unchecked(index) },
+            mask.get(index).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_runs() {
+    let mut mask: BitMask<u8> = BitMask::zeros(12);
+    mask.set(0, true).unwrap();
+    mask.set(1, true).unwrap();
+    mask.set(5, true).unwrap();
+
+    assert_eq!(
+        mask.runs().collect::<Vec<_>>(),
+        vec![(true, 2), (false, 3), (true, 1), (false, 6)]
+    );
+    assert_eq!(BitMask::<u8>::zeros(0).runs().count(), 0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_run_entropy() {
+    let mut alternating: BitMask<u8> = BitMask::zeros(32);
+    for index in (0..32).step_by(2) {
+        alternating.set(index, true).unwrap();
+    }
+    let long_run: BitMask<u8> = BitMask::ones(32);
+
+    assert_eq!(long_run.run_entropy(), 0.0);
+    assert!(alternating.run_entropy() > long_run.run_entropy());
+    assert!((alternating.run_entropy() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_swap() {
+    let mut mask: BitMask<u8> = BitMask::zeros(3);
+    mask.set(0, true).unwrap();
+
+    mask.swap(0, 2).unwrap();
+    assert_eq!(mask.to_string(), "001".to_string());
+
+    mask.swap(2, 2).unwrap();
+    assert_eq!(mask.to_string(), "001".to_string());
+
+    assert!(mask.swap(0, 3).is_err());
+    assert!(mask.swap(3, 0).is_err());
+    assert_eq!(mask.to_string(), "001".to_string());
+}
+
+#[test]
+fn test_select_set() {
+    let mut mask: BitMask<u8> = BitMask::zeros(6);
+    mask.set(0, true).unwrap();
+    mask.set(1, true).unwrap();
+    mask.set(4, true).unwrap();
+
+    let selected = mask.select_set(&[1, 3]);
+    assert_eq!(selected.to_string(), "010000".to_string());
+    assert_eq!(selected.length(), 6);
+
+    assert_eq!(
+        mask.select_set(&[0, 4, 10]).to_string(),
+        "100010".to_string()
+    );
+    assert_eq!(mask.select_set(&[]), BitMask::zeros(6));
+}
+
+#[test]
+fn test_first_last_set() {
+    let mut mask: BitMask<u8> = BitMask::zeros(14);
+    assert_eq!(mask.first_set(), None);
+    assert_eq!(mask.last_set(), None);
+
+    mask.set(1, true).unwrap();
+    mask.set(10, true).unwrap();
+    assert_eq!(mask.first_set(), Some(1));
+    assert_eq!(mask.last_set(), Some(10));
+
+    let mask: BitMask<u64> = BitMask::ones(75);
+    assert_eq!(mask.first_set(), Some(0));
+    assert_eq!(mask.last_set(), Some(74));
+}
+
+#[test]
+fn test_chunk_or() {
+    let mut mask: BitMask<u8> = BitMask::zeros(14);
+    mask.set(1, true).unwrap();
+    mask.set(6, true).unwrap();
+    mask.set(13, true).unwrap();
+
+    let summary = mask.chunk_or(4);
+    assert_eq!(summary.length(), 4);
+    assert_eq!(summary.to_string(), "1101".to_string());
+
+    assert_eq!(mask.chunk_or(1), mask);
+    assert_eq!(mask.chunk_or(20).to_string(), "1".to_string());
+}
+
+#[test]
+fn test_next_set_bit() {
+    let mut mask: BitMask<u8> = BitMask::zeros(30);
+    mask.set(1, true).unwrap();
+    mask.set(5, true).unwrap();
+    mask.set(27, true).unwrap();
+
+    assert_eq!(mask.next_set_bit(0), Some(1));
+    assert_eq!(mask.next_set_bit(1), Some(1));
+    assert_eq!(mask.next_set_bit(2), Some(5));
+    assert_eq!(mask.next_set_bit(6), Some(27));
+    assert_eq!(mask.next_set_bit(28), None);
+    assert_eq!(mask.next_set_bit(100), None);
+}
+
+#[test]
+fn test_chunk_and() {
+    let mut mask: BitMask<u8> = BitMask::zeros(10);
+    for index in [0, 1, 2, 3, 5, 6, 8, 9] {
+        mask.set(index, true).unwrap();
+    }
+
+    let summary = mask.chunk_and(4);
+    assert_eq!(summary.length(), 3);
+    assert_eq!(summary.to_string(), "101".to_string());
+}
+
+#[test]
+fn test_rank() {
+    let mut mask: BitMask<u8> = BitMask::zeros(20);
+    for index in [0, 1, 2, 9, 16] {
+        mask.set(index, true).unwrap();
+    }
+
+    assert_eq!(mask.rank(0), 0);
+    assert_eq!(mask.rank(2), 2);
+    assert_eq!(mask.rank(6), 3);
+    assert_eq!(mask.rank(10), 4);
+    assert_eq!(mask.rank(16), 4);
+    assert_eq!(mask.rank(17), 5);
+    assert_eq!(mask.rank(100), 5);
+}
+
+#[test]
+fn test_dirty_tracking() {
+    let mut mask: BitMask<u8> = BitMask::zeros(20);
+    mask.set(3, true).unwrap();
+    mask.set(8, true).unwrap();
+    let snapshot = mask.clone();
+    assert_eq!(mask.dirty_count(&snapshot), 0);
+
+    mask.set(3, false).unwrap();
+    mask.set(4, true).unwrap();
+    mask.set(19, true).unwrap();
+
+    assert_eq!(mask.dirty_count(&snapshot), 3);
+    assert_eq!(mask.dirty_indices(&snapshot), vec![3, 4, 19]);
+}
+
+#[test]
+fn test_select() {
+    let mut mask: BitMask<u8> = BitMask::zeros(20);
+    for index in [2, 5, 9] {
+        mask.set(index, true).unwrap();
+    }
+
+    assert_eq!(mask.select(0), Some(2));
+    assert_eq!(mask.select(1), Some(5));
+    assert_eq!(mask.select(2), Some(9));
+    assert_eq!(mask.select(3), None);
+    assert_eq!(mask.rank(mask.select(2).unwrap()), 2);
+}
+
+#[test]
+fn test_sieve_primes() {
+    let primes: BitMask<u8> = BitMask::sieve_primes(30);
+    assert_eq!(primes.length(), 30);
+
+    let expected = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+    for index in 0..30 {
+        assert_eq!(primes.get(index).unwrap(), expected.contains(&index));
+    }
+    assert_eq!(primes.count_ones(), expected.len());
+
+    assert_eq!(BitMask::<u8>::sieve_primes(2), BitMask::zeros(2));
+}
+
+#[test]
+fn test_count_ones_in_range() {
+    let mut mask: BitMask<u8> = BitMask::zeros(30);
+    for index in [1, 3, 4, 8, 9, 10, 11, 17, 29] {
+        mask.set(index, true).unwrap();
+    }
+
+    assert_eq!(mask.count_ones_in_range(3..11), 5);
+    assert_eq!(mask.count_ones_in_range(3..=11), 6);
+    assert_eq!(mask.count_ones_in_range(4..5), 1);
+    assert_eq!(mask.count_ones_in_range(0..24), 8);
+    assert_eq!(mask.count_ones_in_range(5..8), 0);
+    assert_eq!(mask.count_ones_in_range(..), mask.count_ones());
+    assert_eq!(mask.count_ones_in_range(12..100), 2);
+    assert_eq!(mask.count_ones_in_range(40..50), 0);
+}
+
+#[test]
+fn test_automaton_step() {
+    let mut mask: BitMask<u8> = BitMask::zeros(9);
+    mask.set(4, true).unwrap();
+
+    let step = mask.automaton_step(90);
+    assert_eq!(step.to_string(), "000101000".to_string());
+    assert_eq!(step.automaton_step(90).to_string(), "001000100".to_string());
+
+    assert_eq!(mask.automaton_step(0), BitMask::zeros(9));
+    assert_eq!(mask.automaton_step(204), mask);
+}
+
+#[test]
+fn test_fill_span() {
+    let mut mask: BitMask<u8> = BitMask::zeros(10);
+    mask.set(2, true).unwrap();
+    mask.set(6, true).unwrap();
+
+    mask.fill_span();
+    assert_eq!(mask.to_string(), "0011111000".to_string());
+
+    let mut empty: BitMask<u8> = BitMask::zeros(10);
+    empty.fill_span();
+    assert_eq!(empty, BitMask::zeros(10));
+}
+
+#[test]
+fn test_match_periodic() {
+    let mut mask: BitMask<u8> = BitMask::zeros(12);
+    for index in [0, 1, 3, 6, 7, 11] {
+        mask.set(index, true).unwrap();
+    }
+
+    assert_eq!(mask.match_periodic(3, 0), 3);
+    assert_eq!(mask.match_periodic(3, 1), 2);
+    assert_eq!(mask.match_periodic(3, 2), 1);
+    assert_eq!(mask.match_periodic(3, 5), 0);
+    assert_eq!(mask.match_periodic(1, 0), mask.count_ones());
+}
+
+#[test]
+fn test_content_hash() {
+    let mut small: BitMask<u8> = BitMask::zeros(70);
+    let mut large: BitMask<u64> = BitMask::zeros(70);
+    for index in [0, 7, 8, 33, 69] {
+        small.set(index, true).unwrap();
+        large.set(index, true).unwrap();
+    }
+    assert_eq!(small.content_hash(), large.content_hash());
+
+    large.set(34, true).unwrap();
+    assert_ne!(small.content_hash(), large.content_hash());
+
+    assert_ne!(
+        BitMask::<u8>::zeros(3).content_hash(),
+        BitMask::<u8>::zeros(4).content_hash()
+    );
+}
+
+#[test]
+fn test_extend() {
+    let mut mask: BitMask<u8> = BitMask::ones(2);
+    let mut other: BitMask<u8> = BitMask::zeros(3);
+    other.set(0, true).unwrap();
+    other.set(2, true).unwrap();
+
+    mask.extend_from_mask(&other);
+    assert_eq!(mask.length(), 5);
+    assert_eq!(mask.to_string(), "11101".to_string());
+
+    let mut mask: BitMask<u8> = BitMask::zeros(5);
+    mask.extend_from_mask(&BitMask::ones(20));
+    assert_eq!(mask.length(), 25);
+    assert_eq!(mask.block_count(), 4);
+    assert_eq!(mask.count_ones(), 20);
+    assert_eq!(mask.first_set(), Some(5));
+    assert_eq!(mask.last_set(), Some(24));
+
+    let mut empty: BitMask<u8> = BitMask::zeros(0);
+    empty.extend_from_mask(&other);
+    assert_eq!(empty, other);
+}
+
+#[test]
+fn test_union_indices() {
+    let mut mask: BitMask<u8> = BitMask::zeros(4);
+    mask.union_indices([1, 3]);
+    assert_eq!(mask.to_string(), "0101".to_string());
+
+    mask.union_indices(vec![0, 10]);
+    assert_eq!(mask.length(), 11);
+    assert_eq!(mask.block_count(), 2);
+    assert_eq!(mask.to_string(), "11010000001".to_string());
+}
+
+#[test]
+fn test_split_at() {
+    let mut mask: BitMask<u8> = BitMask::zeros(5);
+    for index in [0, 1, 2, 4] {
+        mask.set(index, true).unwrap();
+    }
+
+    let (low, high) = mask.split_at(2).unwrap();
+    assert_eq!(low.to_string(), "11".to_string());
+    assert_eq!(high.to_string(), "101".to_string());
+
+    let (low, high) = mask.split_at(5).unwrap();
+    assert_eq!(low, mask);
+    assert_eq!(high, BitMask::zeros(0));
+
+    assert!(mask.split_at(6).is_err());
+
+    let mut mask: BitMask<u8> = BitMask::zeros(30);
+    for index in [3, 9, 17, 29] {
+        mask.set(index, true).unwrap();
+    }
+    let (mut low, high) = mask.split_at(11).unwrap();
+    assert_eq!(low.length(), 11);
+    assert_eq!(low.count_ones(), 2);
+    assert_eq!(high.length(), 19);
+    assert_eq!(high.block_count(), 3);
+    assert_eq!(high.first_set(), Some(6));
+    assert_eq!(high.last_set(), Some(18));
+
+    low.extend_from_mask(&high);
+    assert_eq!(low, mask);
+}
+
+#[test]
+fn test_complement_in_universe() {
+    let mut mask: BitMask<u8> = BitMask::zeros(3);
+    mask.set(1, true).unwrap();
+
+    let complement = mask.complement_in_universe(5).unwrap();
+    assert_eq!(complement.length(), 5);
+    assert_eq!(complement.to_string(), "10111".to_string());
+
+    assert_eq!(mask.complement_in_universe(3).unwrap(), !&mask);
+    assert_eq!(mask.complement_in_universe(20).unwrap().count_ones(), 19);
+    assert_eq!(
+        mask.complement_in_universe(2).unwrap_err().to_string(),
+        "Overflow"
+    );
+}
+
+#[test]
+fn test_index() {
+    let mut mask: BitMask<u8> = BitMask::zeros(10);
+    mask.set(1, true).unwrap();
+    assert!(mask[1]);
+    assert!(!mask[0]);
+    assert!(!mask[9]);
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_index_out_of_bounds() {
+    let mask: BitMask<u8> = BitMask::zeros(10);
+    let _ = mask[99];
+}
+
+#[test]
+fn test_is_gray_successor() {
+    let mut prev: BitMask<u8> = BitMask::zeros(12);
+    prev.set(0, true).unwrap();
+    prev.set(9, true).unwrap();
+
+    let mut next = prev.clone();
+    next.set(10, true).unwrap();
+    assert!(next.is_gray_successor(&prev));
+    assert!(prev.is_gray_successor(&next));
+
+    next.set(0, false).unwrap();
+    assert!(!next.is_gray_successor(&prev));
+    assert!(!prev.is_gray_successor(&prev));
+}
+
+#[test]
+fn test_set_unchecked() {
+    let mut safe: BitMask<u8> = BitMask::zeros(20);
+    let mut unchecked: BitMask<u8> = BitMask::zeros(20);
+    for (index, value) in [(0, true), (7, true), (8, true), (19, true), (7, false)] {
+        safe.set(index, value).unwrap();
+        unsafe { unchecked.set_unchecked(index, value) };
+    }
+    assert_eq!(unchecked, safe);
+    assert_eq!(unchecked.to_string(), "10000000100000000001".to_string());
+}
+
+#[test]
+fn test_write_be_bytes_into() {
+    let mut a: BitMask<u16> = BitMask::zeros(20);
+    a.set(0, true).unwrap();
+    a.set(17, true).unwrap();
+    let mut b: BitMask<u8> = BitMask::zeros(8);
+    b.set(7, true).unwrap();
+
+    let mut buf = vec![0xAA];
+    a.write_be_bytes_into(&mut buf);
+    b.write_be_bytes_into(&mut buf);
+
+    assert_eq!(buf.len(), 1 + a.byte_len() + b.byte_len());
+    assert_eq!(buf[0], 0xAA);
+    assert_eq!(&buf[1..3], &[0x00, 0x01]);
+    assert_eq!(&buf[3..5], &[0x00, 0x02]);
+    assert_eq!(buf[5], 0x80);
+}
+
+#[test]
+fn test_close_left() {
+    let mut mask: BitMask<u8> = BitMask::zeros(10);
+    mask.set(3, true).unwrap();
+    assert_eq!(mask.close_left(2).to_string(), "0001110000".to_string());
+    assert_eq!(mask.close_left(0), mask);
+
+    mask.set(8, true).unwrap();
+    assert_eq!(mask.close_left(3).to_string(), "0001111011".to_string());
+    assert_eq!(mask.close_left(20).to_string(), "0001111111".to_string());
+}
+
+#[test]
+fn test_from_bool_slice() {
+    let mask: BitMask<u8> = BitMask::from_bool_slice(&[true, false, true]);
+    let mut expected: BitMask<u8> = BitMask::zeros(3);
+    expected.set(0, true).unwrap();
+    expected.set(2, true).unwrap();
+    assert_eq!(mask, expected);
+
+    let bits: Vec<bool> = (0..20).map(|index| index % 3 == 0).collect();
+    let mask: BitMask<u8> = BitMask::from_bool_slice(&bits);
+    assert_eq!(mask.length(), 20);
+    assert_eq!(mask.block_count(), 3);
+    assert_eq!(mask.to_string(), "10010010010010010010".to_string());
+
+    assert_eq!(BitMask::<u8>::from_bool_slice(&[]), BitMask::zeros(0));
+}
+
+#[test]
+fn test_common_prefix_len() {
+    let mut a: BitMask<u8> = BitMask::zeros(20);
+    let mut b: BitMask<u8> = BitMask::zeros(12);
+    for index in [0, 2, 3] {
+        a.set(index, true).unwrap();
+        b.set(index, true).unwrap();
+    }
+    a.set(5, true).unwrap();
+    b.set(6, true).unwrap();
+    assert_eq!(a.common_prefix_len(&b), 5);
+    assert_eq!(b.common_prefix_len(&a), 5);
+
+    assert_eq!(a.common_prefix_len(&a), 20);
+    assert_eq!(a.common_prefix_len(&BitMask::zeros(0)), 0);
+
+    let mut c = b.clone();
+    c.set(6, false).unwrap();
+    b.set(6, false).unwrap();
+    b.set(11, true).unwrap();
+    assert_eq!(b.common_prefix_len(&c), 11);
+}
+
+#[test]
+fn test_to_bool_vec() {
+    let bits: Vec<bool> = (0..21).map(|index| index % 4 == 1).collect();
+    let mask: BitMask<u8> = BitMask::from_bool_slice(&bits);
+
+    let round_trip = mask.to_bool_vec();
+    assert_eq!(round_trip.len(), mask.length());
+    assert_eq!(round_trip, bits);
+
+    assert!(BitMask::<u8>::zeros(0).to_bool_vec().is_empty());
+}
+
+#[test]
+fn test_to_indices() {
+    let mut mask: BitMask<u8> = BitMask::zeros(14);
+    for index in [1, 5, 10] {
+        mask.set(index, true).unwrap();
+    }
+    assert_eq!(mask.to_indices(), vec![1, 5, 10]);
+
+    assert_eq!(BitMask::<u64>::ones(3).to_indices(), vec![0, 1, 2]);
+    assert!(BitMask::<u64>::zeros(100).to_indices().is_empty());
+}
+
+#[test]
+fn test_crop_to_content() {
+    let mut mask: BitMask<u8> = BitMask::zeros(30);
+    for index in [11, 12, 14, 20] {
+        mask.set(index, true).unwrap();
+    }
+
+    let offset = mask.crop_to_content();
+    assert_eq!(offset, 11);
+    assert_eq!(mask.length(), 10);
+    assert_eq!(mask.block_count(), 2);
+    assert_eq!(mask.to_string(), "1101000001".to_string());
+
+    let mut empty: BitMask<u8> = BitMask::zeros(30);
+    assert_eq!(empty.crop_to_content(), 0);
+    assert_eq!(empty, BitMask::zeros(0));
+}
+
+#[test]
+fn test_equals_bits() {
+    let mut small: BitMask<u8> = BitMask::zeros(70);
+    let mut large: BitMask<u64> = BitMask::zeros(70);
+    for index in [0, 9, 63, 64, 69] {
+        small.set(index, true).unwrap();
+        large.set(index, true).unwrap();
+    }
+    assert!(small.equals_bits(&large));
+    assert!(large.equals_bits(&small));
+
+    large.set(1, true).unwrap();
+    assert!(!small.equals_bits(&large));
+
+    assert!(!BitMask::<u8>::zeros(3).equals_bits(&BitMask::<u64>::zeros(4)));
+}
+
+#[test]
+fn test_grow_to() {
+    let mut mask: BitMask<u8> = BitMask::zeros(6);
+    mask.set(1, true).unwrap();
+    mask.set(5, true).unwrap();
+
+    mask.grow_to(20);
+    assert_eq!(mask.length(), 20);
+    assert_eq!(mask.block_count(), 3);
+    assert_eq!(mask.to_string(), "01000100000000000000".to_string());
+    mask.set(19, true).unwrap();
+
+    let copy = mask.clone();
+    mask.grow_to(4);
+    assert_eq!(mask, copy);
+}
+
+#[test]
+fn test_contains_all_any() {
+    let mut mask: BitMask<u8> = BitMask::zeros(10);
+    mask.set(2, true).unwrap();
+    mask.set(5, true).unwrap();
+
+    assert!(mask.contains_all(&[2, 5]));
+    assert!(mask.contains_all(&[]));
+    assert!(!mask.contains_all(&[2, 3]));
+    assert!(!mask.contains_all(&[2, 5, 99]));
+
+    assert!(mask.contains_any(&[0, 5]));
+    assert!(!mask.contains_any(&[0, 1, 99]));
+    assert!(!mask.contains_any(&[]));
+}
+
+#[test]
+fn test_ones_count() {
+    assert_eq!(BitMask::<u64>::ones(64).count_ones(), 64);
+    assert_eq!(BitMask::<u64>::ones(128).count_ones(), 128);
+    assert_eq!(BitMask::<u8>::ones(12).count_ones(), 12);
+}
+
+#[test]
+fn test_shift_large_mask() {
+    let mut mask: BitMask<u8> = BitMask::zeros(200);
+    for index in (0..200).filter(|index| index % 3 == 0 || index % 11 == 0) {
+        mask.set(index, true).unwrap();
+    }
+
+    let shifted_left = &mask << 37;
+    let shifted_right = &mask >> 37;
+    for index in 0..200 {
+        assert_eq!(
+            shifted_left.get(index).unwrap(),
+            index >= 37 && mask.get(index - 37).unwrap()
+        );
+        assert_eq!(
+            shifted_right.get(index).unwrap(),
+            index + 37 < 200 && mask.get(index + 37).unwrap()
+        );
+    }
+    assert_eq!(
+        shifted_left.count_ones(),
+        mask.count_ones_in_range(..200 - 37)
+    );
+
+    assert_eq!(&mask << 0, mask);
+    assert_eq!(&mask >> 0, mask);
+    assert_eq!((&mask << 8).to_string(), (&(&mask << 3) << 5).to_string());
+    assert_eq!((&mask >> 16).to_string(), (&(&mask >> 9) >> 7).to_string());
+}
+
+#[test]
+fn test_checked_get() {
+    let mut mask: BitMask<u8> = BitMask::zeros(10);
+    mask.set(3, true).unwrap();
+
+    assert_eq!(mask.checked_get(3), Some(true));
+    assert_eq!(mask.checked_get(4), Some(false));
+    assert_eq!(mask.checked_get(99), None);
+    assert!(!mask.checked_get(99).unwrap_or(false));
+}
+
+#[test]
+fn test_from_bools() {
+    let mask: BitMask<u8> = [true, false, true].into();
+    assert_eq!(mask.length(), 3);
+    assert_eq!(mask.to_string(), "101".to_string());
+
+    let bits: Vec<bool> = (0..12).map(|index| index % 5 == 0).collect();
+    let mask: BitMask<u16> = BitMask::from(bits.clone());
+    assert_eq!(mask.length(), 12);
+    assert_eq!(mask.to_bool_vec(), bits);
+}
+
+#[test]
+fn test_from_u64() {
+    let mask: BitMask<u8> = BitMask::from_u64(0b101, 3);
+    assert_eq!(mask.to_string(), "101".to_string());
+
+    let mask: BitMask<u8> = BitMask::from_u64(0b1111_0110, 4);
+    assert_eq!(mask.to_string(), "0110".to_string());
+
+    let mask: BitMask<u32> = BitMask::from_u64(u64::MAX, 70);
+    assert_eq!(mask.count_ones(), 64);
+    assert_eq!(mask.last_set(), Some(63));
+
+    let mask: BitMask<u64> = BitMask::from_u128(1 << 100, 128);
+    assert_eq!(mask.to_indices(), vec![100]);
+}
+
+#[test]
+fn test_try_into_u64() {
+    let mask: BitMask<u8> = [true, false, true].into();
+    assert_eq!(mask.try_into_u64().unwrap(), 0b101);
+
+    let mask: BitMask<u8> = BitMask::from_u64(0xDEAD_BEEF_0123_4567, 64);
+    assert_eq!(mask.try_into_u64().unwrap(), 0xDEAD_BEEF_0123_4567);
+
+    let mask: BitMask<u64> = BitMask::zeros(100);
+    assert!(mask.try_into_u64().is_err());
+}
+
+#[test]
+fn test_insert() {
+    let mut mask: BitMask<u8> = [true, false, true].into();
+    mask.insert(1, true).unwrap();
+    assert_eq!(mask.length(), 4);
+    assert_eq!(mask.to_string(), "1101".to_string());
+
+    mask.insert(4, false).unwrap();
+    assert_eq!(mask.to_string(), "11010".to_string());
+    assert!(mask.insert(6, true).is_err());
+
+    let mut mask: BitMask<u8> = BitMask::ones(8);
+    mask.insert(0, false).unwrap();
+    assert_eq!(mask.block_count(), 2);
+    assert_eq!(mask.to_string(), "011111111".to_string());
+}
+
+#[test]
+fn test_remove() {
+    let mut mask: BitMask<u8> = [true, true, false, true].into();
+    assert!(mask.remove(1).unwrap());
+    assert_eq!(mask.length(), 3);
+    assert_eq!(mask.to_string(), "101".to_string());
+
+    assert!(mask.remove(2).unwrap());
+    assert_eq!(mask, [true, false].into());
+    assert!(mask.remove(2).is_err());
+
+    let mut mask: BitMask<u8> = BitMask::ones(9);
+    assert!(mask.remove(0).unwrap());
+    assert_eq!(mask, BitMask::ones(8));
+    assert_eq!(mask.block_count(), 1);
+}
+
+#[test]
+fn test_binary_format() {
+    let mask: BitMask<u8> = [true, false, true, true].into();
+    assert_eq!(format!("{:b}", mask), mask.to_string());
+    assert_eq!(format!("{:b}", mask), "1011".to_string());
+    assert_eq!(format!("{:#b}", mask), "0b1011".to_string());
+}
+
+#[test]
+fn test_to_string_msb_first() {
+    let mut mask: BitMask<u8> = BitMask::zeros(3);
+    mask.set(0, true).unwrap();
+    assert_eq!(mask.to_string(), "100".to_string());
+    assert_eq!(mask.to_string_msb_first(), "001".to_string());
+
+    let mask: BitMask<u8> = BitMask::from_u64(0b1_1001_0101, 9);
+    assert_eq!(mask.to_string_msb_first(), "110010101".to_string());
+}
+
+#[test]
+fn test_chunks() {
+    let mask: BitMask<u8> = [
+        true, false, false, true, true, true, false, false, false, true,
+    ]
+    .into();
+
+    let chunks: Vec<BitMask<u8>> = mask.chunks(4).collect();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(
+        chunks.iter().map(|c| c.length()).collect::<Vec<_>>(),
+        vec![4, 4, 2]
+    );
+    assert_eq!(chunks[0].to_string(), "1001".to_string());
+    assert_eq!(chunks[1].to_string(), "1100".to_string());
+    assert_eq!(chunks[2].to_string(), "01".to_string());
+
+    assert_eq!(mask.chunks(20).collect::<Vec<_>>(), vec![mask.clone()]);
+    assert_eq!(BitMask::<u8>::zeros(0).chunks(4).count(), 0);
+}
+
+#[test]
+fn test_windows() {
+    let mask: BitMask<u8> = BitMask::from_u64(0b10_0110_1101, 10);
+
+    let windows: Vec<BitMask<u8>> = mask.windows(3).collect();
+    assert_eq!(windows.len(), 8);
+    assert_eq!(windows[0].to_string(), "101".to_string());
+    assert_eq!(windows[1].to_string(), "011".to_string());
+    assert_eq!(windows[7].to_string(), "001".to_string());
+    for (start, window) in windows.iter().enumerate() {
+        assert_eq!(window.length(), 3);
+        for index in 0..3 {
+            assert_eq!(window[index], mask[start + index]);
+        }
+    }
+
+    assert_eq!(mask.windows(10).collect::<Vec<_>>(), vec![mask.clone()]);
+    assert_eq!(mask.windows(11).count(), 0);
+}
+
+#[test]
+fn test_find_pattern() {
+    let mask: BitMask<u8> = [false, false, true, false, true, false, false].into();
+    let pattern: BitMask<u8> = [true, false, true].into();
+    assert_eq!(mask.find_pattern(&pattern), Some(2));
+
+    let pattern: BitMask<u8> = [true, true].into();
+    assert_eq!(mask.find_pattern(&pattern), None);
+
+    let mut long: BitMask<u8> = BitMask::zeros(40);
+    long.union_indices([30, 31, 33]);
+    let pattern: BitMask<u8> = [true, true, false, true].into();
+    assert_eq!(long.find_pattern(&pattern), Some(30));
+    assert_eq!(pattern.find_pattern(&long), None);
+}
+
+#[test]
+fn test_count_runs() {
+    let mask: BitMask<u8> = [true, true, false, true, true].into();
+    assert_eq!(mask.count_runs(), 2);
+
+    assert_eq!(BitMask::<u8>::ones(20).count_runs(), 1);
+    assert_eq!(BitMask::<u8>::zeros(20).count_runs(), 0);
+
+    let alternating: BitMask<u8> = BitMask::from_u64(0b1010_1010_1010, 12);
+    assert_eq!(alternating.count_runs(), 6);
+}
+
+#[test]
+fn test_from_str() {
+    let mask: BitMask<u8> = "0100110001".parse().unwrap();
+    assert_eq!(mask.length(), 10);
+    assert_eq!(mask.to_indices(), vec![1, 4, 5, 9]);
+    assert_eq!(mask.to_string(), "0100110001".to_string());
+
+    assert_eq!("".parse::<BitMask<u8>>().unwrap(), BitMask::zeros(0));
+    assert!("01x".parse::<BitMask<u8>>().is_err());
+}
+
+#[test]
+fn test_retain() {
+    let mut mask: BitMask<u8> = BitMask::zeros(10);
+    mask.union_indices([1, 2, 4]);
+
+    mask.retain(|index| index % 2 == 0);
+    assert_eq!(mask.to_indices(), vec![2, 4]);
+
+    let calls = std::cell::Cell::new(0);
+    let mut mask: BitMask<u64> = BitMask::zeros(1000);
+    mask.union_indices([3, 500, 999]);
+    mask.retain(|index| {
+        calls.set(calls.get() + 1);
+        index > 100
+    });
+    assert_eq!(calls.get(), 3);
+    assert_eq!(mask.to_indices(), vec![500, 999]);
+}
+
+#[test]
+fn test_set_indices() {
+    let mut mask: BitMask<u8> = BitMask::zeros(10);
+    mask.set_indices(&[0, 4, 9], true).unwrap();
+    assert_eq!(mask.to_string(), "1000100001".to_string());
+
+    mask.set_indices(&[4], false).unwrap();
+    assert_eq!(mask.to_string(), "1000000001".to_string());
+
+    let copy = mask.clone();
+    assert!(mask.set_indices(&[1, 2, 10, 3], true).is_err());
+    assert_eq!(mask, copy);
+}
+
+#[test]
+fn test_leading_trailing_run() {
+    let mask: BitMask<u8> = "0001101111".parse().unwrap();
+    assert_eq!(mask.trailing_run(false), 3);
+    assert_eq!(mask.trailing_run(true), 0);
+    assert_eq!(mask.leading_run(true), 4);
+    assert_eq!(mask.leading_run(false), 0);
+
+    let ones: BitMask<u8> = BitMask::ones(10);
+    assert_eq!(ones.leading_run(true), 10);
+    assert_eq!(ones.trailing_run(true), 10);
+    assert_eq!(ones.leading_run(false), 0);
+
+    let zeros: BitMask<u64> = BitMask::zeros(75);
+    assert_eq!(zeros.leading_run(false), 75);
+    assert_eq!(zeros.trailing_run(false), zeros.trailing_zeros());
+}
+
+#[test]
+fn test_trailing_zeros_all_zero() {
+    assert_eq!(BitMask::<u64>::zeros(75).trailing_zeros(), 75);
+    assert_eq!(BitMask::<u64>::zeros(64).trailing_zeros(), 64);
+    assert_eq!(BitMask::<u8>::zeros(0).trailing_zeros(), 0);
+
+    let mut mask: BitMask<u64> = BitMask::zeros(75);
+    mask.set(74, true).unwrap();
+    assert_eq!(mask.trailing_zeros(), 74);
+}
+
+#[test]
+fn test_reverse_bits() {
+    let mut mask: BitMask<u8> = "1101000001".parse().unwrap();
+    mask.reverse_bits();
+    assert_eq!(mask.to_string(), "1000001011".to_string());
+    mask.reverse_bits();
+    assert_eq!(mask.to_string(), "1101000001".to_string());
+}
+
+#[test]
+fn test_swap_byte_order() {
+    let mut mask: BitMask<u32> = BitMask::from_u64(0x0102_0304_AABB_CCDD, 64);
+
+    let mut bytes = Vec::new();
+    mask.write_be_bytes_into(&mut bytes);
+    assert_eq!(bytes, vec![0xAA, 0xBB, 0xCC, 0xDD, 0x01, 0x02, 0x03, 0x04]);
+
+    mask.swap_byte_order();
+    let mut bytes = Vec::new();
+    mask.write_be_bytes_into(&mut bytes);
+    assert_eq!(bytes, vec![0xDD, 0xCC, 0xBB, 0xAA, 0x04, 0x03, 0x02, 0x01]);
+    assert_eq!(mask.try_into_u64().unwrap(), 0x0403_0201_DDCC_BBAA);
+
+    mask.swap_byte_order();
+    assert_eq!(mask.try_into_u64().unwrap(), 0x0102_0304_AABB_CCDD);
+
+    //The partial last block is left as is, no bit is lost
+    let mut short: BitMask<u16> = BitMask::from_u64(0x00FF, 12);
+    short.swap_byte_order();
+    assert_eq!(short.try_into_u64().unwrap(), 0x00FF);
+    assert_eq!(short.count_ones(), 8);
+
+    let mut partial: BitMask<u16> = BitMask::from_u64(0x0ABC_00FF, 28);
+    partial.swap_byte_order();
+    assert_eq!(partial.try_into_u64().unwrap(), 0x0ABC_FF00);
+    partial.swap_byte_order();
+    assert_eq!(partial.try_into_u64().unwrap(), 0x0ABC_00FF);
+
+    let mut empty: BitMask<u16> = BitMask::zeros(0);
+    empty.swap_byte_order();
+    assert_eq!(empty.length(), 0);
+}
+
+#[test]
+fn test_ones_edge_lengths() {
+    let empty: BitMask<u8> = BitMask::ones(0);
+    assert_eq!(empty, BitMask::zeros(0));
+    assert_eq!(empty.block_count(), 0);
+    assert_eq!(empty.count_ones(), 0);
+
+    let one: BitMask<u64> = BitMask::ones(1);
+    assert_eq!(one.to_string(), "1".to_string());
+    assert_eq!(one.count_ones(), 1);
+
+    let mut mask: BitMask<u8> = BitMask::zeros(0);
+    mask.set_all(true);
+    assert_eq!(mask, BitMask::zeros(0));
+}
+
+#[test]
+fn test_storage_leading_zeros() {
+    assert_eq!(BitStorage::leading_zeros(&1u8), 7);
+    assert_eq!(BitStorage::leading_zeros(&0u16), 16);
+    assert_eq!(BitStorage::leading_zeros(&u64::MAX), 0);
+
+    let mut mask: BitMask<u8> = BitMask::zeros(20);
+    mask.union_indices([2, 13]);
+    assert_eq!(mask.last_set(), Some(13));
+    assert_eq!(BitMask::<u8>::zeros(20).last_set(), None);
+}
+
+#[test]
+fn test_from_le_be_bytes() {
+    let le: BitMask<u16> = BitMask::from_le_bytes(&[0x05, 0x80, 0x03, 0x00], 20).unwrap();
+    let be: BitMask<u16> = BitMask::from_be_bytes(&[0x80, 0x05, 0x00, 0x03], 20).unwrap();
+    assert_eq!(le, be);
+    assert_eq!(le.to_indices(), vec![0, 2, 15, 16, 17]);
+
+    let mut bytes = Vec::new();
+    be.write_be_bytes_into(&mut bytes);
+    assert_eq!(bytes, vec![0x80, 0x05, 0x00, 0x03]);
+
+    assert!(BitMask::<u16>::from_le_bytes(&[0x05, 0x80, 0x03], 20).is_err());
+    assert!(BitMask::<u16>::from_le_bytes(&[0x05, 0x80], 20).is_err());
+    assert_eq!(
+        BitMask::<u16>::from_le_bytes(&[0xFF, 0xFF], 4).unwrap(),
+        BitMask::ones(4)
+    );
+}
+
+#[test]
+fn test_padded_len() {
+    let mut mask: BitMask<u16> = BitMask::ones(37);
+    mask.invert();
+    mask.invert();
+    assert_eq!(mask.padded_len(), 48);
+    assert_eq!(mask.padded_len() % 16, 0);
+
+    let mut bytes = Vec::new();
+    mask.write_be_bytes_into(&mut bytes);
+    let padded: BitMask<u16> = BitMask::from_be_bytes(&bytes, mask.padded_len()).unwrap();
+    assert_eq!(padded.count_ones_in_range(mask.length()..), 0);
+    assert_eq!(padded.count_ones(), 37);
+
+    assert_eq!(BitMask::<u64>::zeros(0).padded_len(), 0);
+    assert_eq!(BitMask::<u64>::zeros(64).padded_len(), 64);
+}
+
+#[test]
+fn test_overlap_count() {
+    let a: BitMask<u8> = "110".parse().unwrap();
+    let b: BitMask<u8> = "011".parse().unwrap();
+    assert_eq!(a.overlap_count(&b), 1);
+
+    let mut c: BitMask<u8> = BitMask::zeros(30);
+    let mut d: BitMask<u8> = BitMask::zeros(20);
+    c.union_indices([1, 9, 17, 25]);
+    d.union_indices([1, 9, 18]);
+    assert_eq!(c.overlap_count(&d), 2);
+    assert_eq!(c.overlap_count(&d), (&c & &d).count_ones());
+}
+
+#[test]
+fn test_jaccard() {
+    let a: BitMask<u8> = "1101".parse().unwrap();
+    let b: BitMask<u8> = "0111".parse().unwrap();
+    assert_eq!(a.jaccard(&b), 0.5);
+    assert_eq!(a.jaccard(&a), 1.0);
+
+    let empty: BitMask<u8> = BitMask::zeros(4);
+    assert_eq!(empty.jaccard(&empty), 0.0);
+    assert_eq!(a.jaccard(&empty), 0.0);
+}
+
+#[test]
+fn test_hamming_distance() {
+    let a: BitMask<u8> = "101".parse().unwrap();
+    let b: BitMask<u8> = "110".parse().unwrap();
+    assert_eq!(a.hamming_distance(&b), 2);
+    assert_eq!(a.hamming_distance(&a), 0);
+
+    let long: BitMask<u8> = "1010000000011".parse().unwrap();
+    assert_eq!(a.hamming_distance(&long), 2);
+    assert_eq!(long.hamming_distance(&a), 2);
+}
+
+#[test]
+fn test_bits_rev() {
+    let mask: BitMask<u8> = "1101000001100".parse().unwrap();
+
+    let forward: Vec<bool> = mask.bits().collect();
+    assert_eq!(forward, mask.to_bool_vec());
+
+    let mut backward: Vec<bool> = mask.bits().rev().collect();
+    assert_eq!(backward.len(), 13);
+    backward.reverse();
+    assert_eq!(backward, forward);
+
+    let mut bits = mask.bits();
+    assert_eq!(bits.len(), 13);
+    assert_eq!(bits.next(), Some(true));
+    assert_eq!(bits.next_back(), Some(false));
+    assert_eq!(bits.next_back(), Some(false));
+    assert_eq!(bits.next_back(), Some(true));
+    assert_eq!(bits.len(), 9);
+
+    let ones: BitMask<u8> = BitMask::ones(10);
+    assert_eq!(ones.bits().rev().filter(|&b| b).count(), 10);
+}
+
+#[test]
+fn test_set_or_grow() {
+    let mut mask: BitMask<u8> = BitMask::zeros(3);
+    mask.set_or_grow(20, true);
+    assert_eq!(mask.length(), 21);
+    assert_eq!(mask.block_count(), 3);
+    assert_eq!(mask.count_ones(), 1);
+    assert!(mask.get(20).unwrap());
+
+    mask.set_or_grow(1, true);
+    assert_eq!(mask.length(), 21);
+    mask.set_or_grow(20, false);
+    assert_eq!(mask.length(), 21);
+    assert_eq!(mask.to_indices(), vec![1]);
+}
+
+#[test]
+fn test_extend_trait() {
+    let mut mask: BitMask<u8> = "101".parse().unwrap();
+    mask.extend([true, true, false, false, false, false, true]);
+    assert_eq!(mask.length(), 10);
+    assert_eq!(mask.block_count(), 2);
+    assert_eq!(mask.to_string(), "1011100001");
+
+    mask.extend(core::iter::empty::<bool>());
+    assert_eq!(mask.length(), 10);
+
+    let mut mask: BitMask<u8> = BitMask::zeros(4);
+    mask.extend(vec![2usize, 12, 0]);
+    assert_eq!(mask.length(), 13);
+    assert_eq!(mask.to_indices(), vec![0, 2, 12]);
+}
+
+#[test]
+fn test_display_from_str_round_trip() {
+    use std::str::FromStr;
+
+    let mut rng = lcg(7);
+    let mut next = move || rng() as usize;
+
+    for _ in 0..200 {
+        let length = next() % 300;
+        let mut small: BitMask<u8> = BitMask::zeros(length);
+        let mut wide: BitMask<u64> = BitMask::zeros(length);
+        for _ in 0..next() % (length + 1) {
+            let index = next() % length;
+            small.set(index, true).unwrap();
+            wide.set(index, true).unwrap();
+        }
+
+        let text = small.to_string();
+        assert_eq!(text.len(), length);
+        assert_eq!(BitMask::<u8>::from_str(&text).unwrap(), small);
+        assert_eq!(wide.to_string(), text);
+        assert_eq!(BitMask::<u64>::from_str(&text).unwrap(), wide);
+    }
+}
+
+#[test]
+fn test_assign_operators_length() {
+    let short: BitMask<u8> = "101".parse().unwrap();
+    let long: BitMask<u8> = "0110000001".parse().unwrap();
+
+    let mut or = short.clone();
+    or |= &long;
+    assert_eq!(or.length(), 10);
+    assert_eq!(or.block_count(), 2);
+    assert_eq!(or.to_string(), "1110000001");
+    let mut or = long.clone();
+    or |= &short;
+    assert_eq!(or.to_string(), "1110000001");
+
+    let mut xor = short.clone();
+    xor ^= &long;
+    assert_eq!(xor.length(), 10);
+    assert_eq!(xor.block_count(), 2);
+    assert_eq!(xor.to_string(), "1100000001");
+    let mut xor = long.clone();
+    xor ^= &short;
+    assert_eq!(xor.to_string(), "1100000001");
+
+    let mut and = short.clone();
+    and &= &long;
+    assert_eq!(and.length(), 3);
+    assert_eq!(and.block_count(), 1);
+    assert_eq!(and.to_string(), "001");
+    let mut and = long.clone();
+    and &= &short;
+    assert_eq!(and.length(), 3);
+    assert_eq!(and.block_count(), 1);
+    assert_eq!(and.to_string(), "001");
+    assert_eq!(and.count_ones(), 1);
+
+    assert_eq!((&short | &long).length(), 10);
+    assert_eq!((&short ^ &long).length(), 10);
+    assert_eq!((&long & &short).length(), 3);
+}
+
+#[test]
+fn test_count_ones_unrolled() {
+    //Lengths around multiples of 4 blocks exercise both the unrolled groups and the remainder
+    for length in [0, 1, 63, 64, 200, 255, 256, 257, 320, 1000] {
+        let mut mask: BitMask<u64> = BitMask::zeros(length);
+        for index in (0..length).filter(|i| i % 3 == 0 || i % 7 == 0) {
+            mask.set(index, true).unwrap();
+        }
+        let naive = (0..length).filter(|&i| mask.get(i).unwrap()).count();
+        assert_eq!(mask.count_ones(), naive);
+
+        let ones: BitMask<u8> = BitMask::ones(length);
+        assert_eq!(ones.count_ones(), length);
+    }
+}
+
+#[test]
+fn test_shift_in() {
+    let stream = [true, false, true, true, false, false, true];
+
+    let mut register: BitMask<u8> = BitMask::zeros(4);
+    let mut states = vec![];
+    for &bit in &stream {
+        register.shift_left_in(bit);
+        states.push(register.to_string());
+    }
+    assert_eq!(
+        states,
+        vec!["1000", "0100", "1010", "1101", "0110", "0011", "1001"]
+    );
+
+    let mut register: BitMask<u8> = BitMask::zeros(4);
+    let mut states = vec![];
+    for &bit in &stream {
+        register.shift_right_in(bit);
+        states.push(register.to_string());
+    }
+    assert_eq!(
+        states,
+        vec!["0001", "0010", "0101", "1011", "0110", "1100", "1001"]
+    );
+
+    let mut wide: BitMask<u8> = BitMask::zeros(12);
+    for _ in 0..12 {
+        wide.shift_left_in(true);
+    }
+    wide.shift_left_in(false);
+    assert_eq!(wide.to_string(), "011111111111");
+    assert_eq!(wide.padded_len() - wide.length(), 4);
+    assert_eq!(wide.count_ones(), 11);
+
+    let mut empty: BitMask<u8> = BitMask::zeros(0);
+    empty.shift_left_in(true);
+    empty.shift_right_in(true);
+    assert_eq!(empty.length(), 0);
+}
+
+#[test]
+fn test_has_single_bit() {
+    let mut mask: BitMask<u8> = BitMask::zeros(20);
+    assert!(!mask.has_single_bit());
+
+    mask.set(5, true).unwrap();
+    assert!(mask.has_single_bit());
+
+    mask.set(6, true).unwrap();
+    assert!(!mask.has_single_bit());
+
+    mask.set(5, false).unwrap();
+    mask.set(6, false).unwrap();
+    mask.set(1, true).unwrap();
+    mask.set(17, true).unwrap();
+    assert!(!mask.has_single_bit());
+
+    let one: BitMask<u8> = BitMask::ones(1);
+    assert!(one.has_single_bit());
+}
+
+#[test]
+fn test_block_slice_round_trip() {
+    let mask: BitMask<u8> = "1000000011".parse().unwrap();
+    assert_eq!(mask.as_block_slice(), &[0b0000_0001, 0b0000_0011]);
+
+    let rebuilt = BitMask::from_blocks(mask.as_block_slice().to_vec(), mask.length()).unwrap();
+    assert_eq!(rebuilt, mask);
+
+    //Bits beyond length and extra blocks are dropped
+    let mask: BitMask<u8> = BitMask::from_blocks(vec![0xFF, 0xFF, 0xFF], 10).unwrap();
+    assert_eq!(mask.as_block_slice(), &[0xFF, 0b0000_0011]);
+    assert_eq!(mask.count_ones(), 10);
+
+    assert_eq!(
+        BitMask::<u8>::from_blocks(vec![0xFF], 9)
+            .unwrap_err()
+            .to_string(),
+        "Overflow"
+    );
+    assert_eq!(
+        BitMask::<u8>::from_be_bytes(&[0xFF], 9)
+            .unwrap_err()
+            .to_string(),
+        "DeserializationFailed"
+    );
+    assert_eq!(BitMask::<u16>::from_blocks(vec![], 0).unwrap().length(), 0);
+}
+
+#[test]
+fn test_repeat() {
+    let pattern: BitMask<u8> = "10".parse().unwrap();
+    assert_eq!(BitMask::repeat(&pattern, 3).to_string(), "101010");
+    assert_eq!(BitMask::repeat(&pattern, 0).length(), 0);
+
+    //A 3-bit pattern doesn't divide the block size, copies straddle block boundaries
+    let pattern: BitMask<u8> = "110".parse().unwrap();
+    let striped = BitMask::repeat(&pattern, 7);
+    assert_eq!(striped.length(), 21);
+    assert_eq!(striped.block_count(), 3);
+    assert_eq!(striped.to_string(), "110".repeat(7));
+    assert_eq!(striped.count_ones(), 14);
+}
+
+#[test]
+fn test_run_length_round_trip() {
+    let mask: BitMask<u8> = "11100".parse().unwrap();
+    assert_eq!(mask.run_length_encode(), vec![(true, 3), (false, 2)]);
+
+    for pattern in [
+        "",
+        "0",
+        "1",
+        "0110001111",
+        "10101010101",
+        "0000000000000000011",
+    ] {
+        let mask: BitMask<u8> = pattern.parse().unwrap();
+        let runs = mask.run_length_encode();
+        assert_eq!(
+            runs.iter().map(|&(_, len)| len).sum::<usize>(),
+            pattern.len()
+        );
+        assert_eq!(BitMask::<u8>::from_run_length(&runs), mask);
+    }
+
+    //Runs of the same value or of length 0 are accepted, the encoding always merges them
+    let mask: BitMask<u16> =
+        BitMask::from_run_length(&[(true, 2), (true, 1), (false, 0), (false, 20)]);
+    assert_eq!(mask.length(), 23);
+    assert_eq!(mask.run_length_encode(), vec![(true, 3), (false, 20)]);
+}
+
+#[test]
+fn test_debug_shows_bits() {
+    let mut mask: BitMask<u8> = BitMask::zeros(5);
+    mask.set(1, true).unwrap();
+    mask.set(2, true).unwrap();
+    assert_eq!(
+        format!("{:?}", mask),
+        "BitMask { bits: \"01100\", length: 5 }"
+    );
+
+    let wide: BitMask<u64> = BitMask::ones(70);
+    let debug = format!("{:?}", wide);
+    assert!(debug.contains(&"1".repeat(70)));
+    assert!(debug.contains("length: 70"));
+}
+
+#[test]
+fn test_rank_index() {
+    let mut rng = lcg(1234);
+    let mut next = move || rng() as usize;
+
+    let length = 20_003;
+    let mut wide: BitMask<u64> = BitMask::zeros(length);
+    let mut small: BitMask<u8> = BitMask::zeros(length);
+    for _ in 0..5_000 {
+        let index = next() % length;
+        wide.set(index, true).unwrap();
+        small.set(index, true).unwrap();
+    }
+    //A sparse stretch of empty blocks
+    for index in 8_000..12_000 {
+        wide.set(index, false).unwrap();
+        small.set(index, false).unwrap();
+    }
+
+    let wide_index = wide.build_rank_index();
+    let small_index = small.build_rank_index();
+    assert_eq!(wide_index.count_ones(), wide.count_ones());
+
+    for index in (0..length + 10).step_by(7).chain([length - 1, length]) {
+        assert_eq!(wide_index.rank(index), wide.rank(index));
+        assert_eq!(small_index.rank(index), small.rank(index));
+    }
+    for n in 0..wide.count_ones() + 5 {
+        assert_eq!(wide_index.select(n), wide.select(n));
+        assert_eq!(small_index.select(n), small.select(n));
+    }
+
+    let empty: BitMask<u32> = BitMask::zeros(0);
+    let empty_index = empty.build_rank_index();
+    assert_eq!(empty_index.rank(3), 0);
+    assert_eq!(empty_index.select(0), None);
+}
+
+#[test]
+fn test_try_operators() {
+    let a: BitMask<u8> = "110".parse().unwrap();
+    let b: BitMask<u8> = "0111".parse().unwrap();
+    assert!(a.try_and(&b).is_err());
+    assert!(a.try_or(&b).is_err());
+    assert!(b.try_xor(&a).is_err());
+    assert_eq!(a.try_and(&b).unwrap_err().to_string(), "LengthMismatch");
+
+    let c: BitMask<u8> = "011".parse().unwrap();
+    assert_eq!(a.try_and(&c).unwrap().to_string(), "010");
+    assert_eq!(a.try_or(&c).unwrap().to_string(), "111");
+    assert_eq!(a.try_xor(&c).unwrap().to_string(), "101");
+}
+
+#[test]
+fn test_significant_length() {
+    let mut mask: BitMask<u8> = BitMask::zeros(20);
+    assert_eq!(mask.significant_length(), 0);
+
+    mask.set(2, true).unwrap();
+    mask.set(6, true).unwrap();
+    assert_eq!(mask.significant_length(), 7);
+
+    mask.set(19, true).unwrap();
+    assert_eq!(mask.significant_length(), 20);
+}
+
+#[test]
+fn test_chunks_windows_across_blocks() {
+    let mut mask: BitMask<u8> = BitMask::zeros(45);
+    for index in [0, 3, 7, 8, 15, 16, 22, 31, 32, 40, 44] {
+        mask.set(index, true).unwrap();
+    }
+    let bits = mask.to_bool_vec();
+
+    for n in [1, 3, 8, 11, 17, 45] {
+        for (chunk, expected) in mask.chunks(n).zip(bits.chunks(n)) {
+            assert_eq!(chunk.to_bool_vec(), expected);
+        }
+        for (window, expected) in mask.windows(n).zip(bits.windows(n)) {
+            assert_eq!(window.to_bool_vec(), expected);
+        }
+    }
+
+    //Each item only reads the blocks it covers
+    let mut large: BitMask<u64> = BitMask::zeros(1_000_000);
+    large.set(500_000, true).unwrap();
+    assert_eq!(large.chunks(8).count(), 125_000);
+    assert_eq!(large.chunks(8).nth(62_500).unwrap().to_string(), "10000000");
+    assert_eq!(
+        large.windows(64).filter(|w| w.count_ones() == 1).count(),
+        64
+    );
+}
+
+#[test]
+fn test_find_pattern_multi_block() {
+    let mut mask: BitMask<u8> = BitMask::zeros(60);
+    mask.union_indices([5, 13, 21, 22, 23, 29, 30, 44]);
+    let bits = mask.to_bool_vec();
+
+    //Patterns spanning several blocks, cut at unaligned offsets of the mask
+    for (start, len) in [(13, 17), (21, 9), (5, 40), (0, 60), (29, 16)] {
+        let pattern = BitMask::<u8>::from_bool_slice(&bits[start..start + len]);
+        let expected = bits
+            .windows(len)
+            .position(|window| window == &bits[start..start + len]);
+        assert_eq!(mask.find_pattern(&pattern), expected);
+    }
+
+    let absent: BitMask<u8> = BitMask::ones(4);
+    assert_eq!(mask.find_pattern(&absent), None);
+
+    let mut large: BitMask<u64> = BitMask::zeros(1_000_000);
+    large.union_indices([999_990, 999_992]);
+    let pattern: BitMask<u64> = "101".parse().unwrap();
+    assert_eq!(large.find_pattern(&pattern), Some(999_990));
+}
+
+#[test]
+fn test_insert_across_blocks() {
+    let pattern = "1011001110001111010";
+    for index in 0..=pattern.len() {
+        for value in [false, true] {
+            let mut mask: BitMask<u8> = pattern.parse().unwrap();
+            mask.insert(index, value).unwrap();
+
+            let mut expected = pattern.to_string();
+            expected.insert(index, if value { '1' } else { '0' });
+            assert_eq!(mask.to_string(), expected);
+            assert_eq!(mask.count_ones(), expected.matches('1').count());
+        }
+    }
+
+    let mut mask: BitMask<u16> = BitMask::ones(16);
+    mask.insert(3, false).unwrap();
+    assert_eq!(mask.block_count(), 2);
+    assert_eq!(mask.to_string(), "11101111111111111");
+    assert_eq!(!&mask, BitMask::from_u64(0b1000, 17));
+}
+
+#[test]
+fn test_remove_across_blocks() {
+    let pattern = "1011001110001111010";
+    for index in 0..pattern.len() {
+        let mut mask: BitMask<u8> = pattern.parse().unwrap();
+        let removed = mask.remove(index).unwrap();
+
+        let mut expected = pattern.to_string();
+        assert_eq!(removed, expected.remove(index) == '1');
+        assert_eq!(mask.to_string(), expected);
+        assert_eq!(mask.count_ones(), expected.matches('1').count());
+        assert_eq!(mask.block_count(), expected.len().div_ceil(8));
+    }
+
+    let mut mask: BitMask<u16> = BitMask::ones(17);
+    assert!(mask.remove(3).unwrap());
+    assert_eq!(mask, BitMask::ones(16));
+    assert_eq!(mask.block_count(), 1);
+}
+
+#[test]
+fn test_dirty_indices_lengths() {
+    let mut mask: BitMask<u8> = BitMask::zeros(30);
+    mask.union_indices([0, 7, 8, 29]);
+    let mut snapshot: BitMask<u8> = BitMask::zeros(12);
+    snapshot.union_indices([7, 9, 11]);
+
+    assert_eq!(mask.dirty_indices(&snapshot), vec![0, 8, 9, 11, 29]);
+    assert_eq!(snapshot.dirty_indices(&mask), vec![0, 8, 9, 11, 29]);
+    assert_eq!(mask.dirty_indices(&mask), Vec::<usize>::new());
+    assert_eq!(
+        mask.dirty_indices(&snapshot).len(),
+        mask.hamming_distance(&snapshot)
+    );
+}
+
+#[test]
+fn test_last_set_every_offset() {
+    //Every position of the highest bit set, including the top and bottom bit of each block
+    for length in [1, 7, 8, 9, 64, 100] {
+        for index in 0..length {
+            let mut small: BitMask<u8> = BitMask::zeros(length);
+            let mut wide: BitMask<u64> = BitMask::zeros(length);
+            small.union_indices([0, index]);
+            wide.union_indices([0, index]);
+            assert_eq!(small.last_set(), Some(index));
+            assert_eq!(wide.last_set(), Some(index));
+        }
+    }
+}
+
+#[test]
+fn test_runs_across_blocks() {
+    //A run of ones then a run of zeros, cut at every position, for lengths around block boundaries
+    for length in [0, 1, 7, 8, 9, 16, 17, 64, 65, 130] {
+        for cut in 0..=length {
+            let bits: Vec<bool> = (0..length).map(|index| index < cut).collect();
+            let small = BitMask::<u8>::from_bool_slice(&bits);
+            let wide = BitMask::<u64>::from_bool_slice(&bits);
+            for value in [false, true] {
+                let trailing = bits.iter().take_while(|&&b| b == value).count();
+                let leading = bits.iter().rev().take_while(|&&b| b == value).count();
+                assert_eq!(small.trailing_run(value), trailing);
+                assert_eq!(wide.trailing_run(value), trailing);
+                assert_eq!(small.leading_run(value), leading);
+                assert_eq!(wide.leading_run(value), leading);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_cached_count_after_mutations() {
+    let mut cached: CachedBitMask<u8> = BitMask::zeros(50).into();
+    let mut other: BitMask<u8> = BitMask::zeros(50);
+    let mut rng = lcg(42);
+    let mut next = move || rng() as usize;
+
+    for _ in 0..500 {
+        let index = next() % 50;
+        match next() % 8 {
+            0 => cached.set_all(next() % 2 == 0),
+            1 => cached |= &other,
+            2 => cached &= &other,
+            3 => cached ^= &other,
+            4 => cached <<= next() % 10,
+            5 => cached >>= next() % 10,
+            6 => other.set(index, next() % 2 == 0).unwrap(),
+            _ => cached.set(index, next() % 2 == 0).unwrap(),
+        }
+        assert_eq!(cached.count_ones(), (*cached).count_ones());
+    }
+
+    assert!(cached.set(50, true).is_err());
+    cached.clear();
+    assert_eq!(cached.count_ones(), 0);
+    assert_eq!(cached.into_inner(), BitMask::zeros(50));
+}